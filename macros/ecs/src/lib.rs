@@ -15,3 +15,16 @@ pub fn derive_component(input: TokenStream) -> TokenStream {
         impl #path::component::Component for #struct_name {}
     })
 }
+
+#[proc_macro_derive(Resource)]
+pub fn derive_resource(input: TokenStream) -> TokenStream {
+    let ast: syn::DeriveInput = syn::parse(input).unwrap();
+
+    let struct_name = &ast.ident;
+
+    let path = path!(::woody::ecs);
+
+    TokenStream::from(quote! {
+        impl #path::resource::Resource for #struct_name {}
+    })
+}
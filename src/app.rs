@@ -1,6 +1,9 @@
 use std::time::Duration;
 use std::time::Instant;
 
+use log::debug;
+use log::error;
+use log::warn;
 use thiserror::Error;
 
 use winit::event::DeviceEvent;
@@ -8,17 +11,48 @@ use winit::event::Event;
 use winit::event::WindowEvent;
 use winit::event_loop::ControlFlow;
 use winit::event_loop::EventLoop;
+use winit::event_loop::EventLoopWindowTarget;
 
+use crate::ecs::resource::Resource;
 use crate::ecs::world::World;
+use crate::event::take_exit;
 use crate::event::CreateEvent;
-use crate::event::UpdateEvent;
+use crate::event::FocusChangedEvent;
+use crate::event::ResumedEvent;
+use crate::event::ShutdownEvent;
+use crate::event::SuspendedEvent;
 use crate::graphics::camera::Camera;
 use crate::graphics::renderer::Renderer;
+use crate::graphics::renderer::RendererError;
+use crate::graphics::GraphicsError;
+use crate::input;
+use crate::input::gamepad::GamepadBackend;
 use crate::input::keyboard::KeyboardEvent;
+use crate::input::keyboard::KeyboardState;
+use crate::input::keyboard::Modifiers;
+use crate::input::replay::InputRecording;
+use crate::input::replay::InputReplay;
+use crate::input::replay::RecordedInput;
+use crate::input::text::Ime;
+use crate::input::text::ImeEvent;
+use crate::input::text::TextInputEvent;
 use crate::input::CursorEvent;
 use crate::input::MouseEvent;
 use crate::input::MouseMotionEvent;
+use crate::input::MouseWheelEvent;
+use crate::plugin::Plugin;
+use crate::plugin::PluginGroup;
+use crate::state::States;
 use crate::systems::Systems;
+use crate::window::Cursor;
+use crate::window::WindowDescriptor;
+
+use self::frame::FrameRunner;
+use self::headless::HeadlessApp;
+use self::headless::HeadlessClock;
+
+mod frame;
+pub mod headless;
 
 #[derive(Debug, Clone, Copy)]
 pub struct GameState {
@@ -26,74 +60,113 @@ pub struct GameState {
     pub last_time: f64,
 }
 
+/// A hook invoked with the error that stopped the app, right before it shuts down.
+pub type ErrorHook = fn(&mut World, &Error);
+
 pub struct App {
     pub world: World,
     pub systems: Systems,
     renderer: Renderer,
     state: GameState,
     clock: Clock,
+    error_hook: Option<ErrorHook>,
+    /// The modifiers held down, tracked for the keyboard events.
+    modifiers: Modifiers,
+    /// The last [`Ime`] settings applied to the window.
+    ime: Ime,
+    /// The last [`Cursor`] settings applied to the window.
+    cursor: Cursor,
+    runner: FrameRunner,
 }
 
 impl App {
-    /// Creates a new App.
+    /// Creates a new App without plugins.
     pub fn new() -> Result<(Self, EventLoop<()>), Error> {
-        #[cfg(debug_assertions)]
-        pretty_env_logger::formatted_builder()
-            .filter_level(log::LevelFilter::Debug)
-            .init();
-
-        let world = World::new();
+        AppBuilder::new().build()
+    }
 
-        let event_loop = EventLoop::new()?;
-        let systems = Systems::default();
-        let renderer = Renderer::new(&event_loop).expect("creating renderer frontend");
-        let state = GameState {
-            delta_time: 0.0,
-            last_time: 0.0,
-        };
+    /// Returns an [`AppBuilder`] to add plugins before creating the app.
+    pub fn builder() -> AppBuilder {
+        AppBuilder::new()
+    }
 
-        Ok((
-            Self {
-                world,
-                systems,
-                renderer,
-                clock: Clock::new(),
-                state,
-            },
-            event_loop,
-        ))
+    /// Adds the state machine `S` starting at `initial`, see [`States`].
+    pub fn init_state<S: States>(&mut self, initial: S) {
+        self.systems.init_state(&mut self.world, initial);
     }
 
+    /// Runs the app until the window is closed or [`AppExit`](crate::event::AppExit) is sent.
+    ///
+    /// Note: when the renderer fails, the error hook is invoked and the app shuts down, returning
+    /// the error.
     pub fn run(mut self, event_loop: EventLoop<()>) -> Result<(), Error> {
         let mut minimized = false;
+        let mut failure = None;
+        let failed = &mut failure;
 
         self.systems.fire(&mut self.world, self.state, CreateEvent);
+        self.systems
+            .apply_state_transitions(&mut self.world, self.state);
 
         self.clock.start();
-        self.clock.update();
-
-        self.state.last_time = self.clock.elapsed;
 
         event_loop.run(move |event, window_target| {
             window_target.set_control_flow(ControlFlow::Poll);
 
+            if let Event::LoopExiting = event {
+                self.systems
+                    .fire(&mut self.world, self.state, ShutdownEvent);
+                return;
+            }
+
+            // Nothing runs anymore while shutting down after an error.
+            if failed.is_some() {
+                return;
+            }
+
             match event {
-                Event::AboutToWait => self.renderer.window.request_redraw(),
+                Event::AboutToWait => {
+                    if let Some(exit) = take_exit(&mut self.world) {
+                        self.systems.fire(&mut self.world, self.state, exit);
+                        window_target.exit();
+                    } else {
+                        self.renderer.window.request_redraw();
+                    }
+                }
+
+                Event::Suspended => {
+                    self.systems
+                        .fire(&mut self.world, self.state, SuspendedEvent);
+                }
+
+                Event::Resumed => {
+                    self.systems.fire(&mut self.world, self.state, ResumedEvent);
+                }
+
                 Event::WindowEvent { event, .. } => match event {
                     WindowEvent::RedrawRequested if !minimized => {
                         self.clock.update();
-                        let current_time = self.clock.elapsed;
-                        self.state.delta_time = current_time - self.state.last_time;
+                        let current_time = self.clock.elapsed.as_secs_f64();
+                        self.state.delta_time = self.clock.delta.as_secs_f64();
 
                         let frame_start_time = Instant::now();
 
-                        self.systems.fire(&mut self.world, self.state, UpdateEvent);
+                        self.runner.run(
+                            &mut self.world,
+                            &mut self.systems,
+                            self.state,
+                            self.clock.delta,
+                        );
+                        self.sync_window();
 
                         if let Some(cam) = self.world.query::<&Camera>().iter().next() {
                             self.renderer.set_view(cam.view())
                         };
 
-                        self.renderer.draw_frame().unwrap();
+                        if let Err(err) = self.renderer.draw_frame() {
+                            *failed = Some(self.fail(err.into(), window_target));
+                            return;
+                        }
 
                         let _frame_elapsed_time = frame_start_time.elapsed().as_secs_f64();
 
@@ -104,33 +177,61 @@ impl App {
                         window_target.exit();
                     }
 
+                    WindowEvent::Focused(focused) => {
+                        if !focused {
+                            self.modifiers = Modifiers::default();
+                        }
+
+                        let event = FocusChangedEvent { focused };
+
+                        self.input(RecordedInput::Focus(event));
+                    }
+
                     WindowEvent::Resized(size) => {
                         if size.width == 0 || size.height == 0 {
                             minimized = true;
                         } else {
                             minimized = false;
-                            self.renderer.resize().unwrap();
+
+                            if let Err(err) = self.renderer.resize() {
+                                *failed = Some(self.fail(err.into(), window_target));
+                            }
                         }
                     }
 
+                    WindowEvent::ModifiersChanged(modifiers) => {
+                        self.modifiers = modifiers.state().into();
+                    }
+
                     WindowEvent::KeyboardInput { event, .. } => {
-                        let winit::event::KeyEvent {
-                            state,
-                            physical_key,
-                            ..
-                        } = event;
+                        let text = event.text.as_deref().and_then(TextInputEvent::new);
 
-                        if let winit::keyboard::PhysicalKey::Code(keycode) = physical_key {
-                            let event = KeyboardEvent::new(state, keycode);
+                        let event = KeyboardEvent::new(&event, self.modifiers);
+                        let state = event.state;
 
-                            self.systems.fire(&mut self.world, self.state, event);
-                        };
+                        self.input(RecordedInput::Keyboard(event));
+
+                        if let (KeyboardState::Pressed, Some(text)) = (state, text) {
+                            self.input(RecordedInput::Text(text));
+                        }
+                    }
+
+                    WindowEvent::Ime(ime) => {
+                        let event = ImeEvent::from(ime);
+
+                        self.input(RecordedInput::Ime(event));
                     }
 
                     WindowEvent::MouseInput { state, button, .. } => {
                         let event = MouseEvent::new(state, button);
 
-                        self.systems.fire(&mut self.world, self.state, event);
+                        self.input(RecordedInput::Mouse(event));
+                    }
+
+                    WindowEvent::MouseWheel { delta, .. } => {
+                        let event = MouseWheelEvent::new(delta, false);
+
+                        self.input(RecordedInput::MouseWheel(event));
                     }
 
                     WindowEvent::CursorMoved { position, .. } => {
@@ -139,7 +240,7 @@ impl App {
                             y: position.y,
                         };
 
-                        self.systems.fire(&mut self.world, self.state, event);
+                        self.input(RecordedInput::Cursor(event));
                     }
 
                     _ => {}
@@ -153,10 +254,14 @@ impl App {
                             delta: delta.into(),
                         };
 
-                        self.systems.fire(&mut self.world, self.state, event);
+                        self.input(RecordedInput::MouseMotion(event));
                     }
 
-                    DeviceEvent::MouseWheel { delta: _ } => todo!(),
+                    DeviceEvent::MouseWheel { delta } => {
+                        let event = MouseWheelEvent::new(delta, true);
+
+                        self.input(RecordedInput::MouseWheel(event));
+                    }
 
                     _ => {}
                 },
@@ -165,36 +270,307 @@ impl App {
             }
         })?;
 
-        Ok(())
+        failure.map_or(Ok(()), Err)
+    }
+
+    /// Fires a live input, unless inputs are being replayed.
+    fn input(&mut self, input: RecordedInput) {
+        if !self.runner.is_replaying() {
+            let frame = self.runner.frame();
+            input.fire(&mut self.world, &mut self.systems, self.state, frame);
+        }
+    }
+
+    /// Applies the changes made by the systems to the window settings.
+    fn sync_window(&mut self) {
+        if let Some(ime) = self.world.resource::<Ime>() {
+            if *ime != self.ime {
+                self.renderer.set_ime(ime);
+                self.ime = *ime;
+            }
+        }
+
+        if let Some(cursor) = self.world.resource_mut::<Cursor>() {
+            if *cursor != self.cursor {
+                cursor.apply(&self.renderer.window, &self.cursor);
+                self.cursor = *cursor;
+            }
+        }
+    }
+
+    /// Reports an error to the error hook and exits the event loop.
+    fn fail(&mut self, error: Error, window_target: &EventLoopWindowTarget<()>) -> Error {
+        error!("Shutting down: {error}");
+
+        if let Some(hook) = self.error_hook {
+            hook(&mut self.world, &error);
+        }
+
+        window_target.exit();
+        error
+    }
+}
+
+/// Builds an [`App`] out of [`Plugin`]s, see [`App::builder`].
+#[derive(Debug, Default)]
+pub struct AppBuilder {
+    pub world: World,
+    pub systems: Systems,
+    /// The names of the plugins added so far.
+    plugins: Vec<String>,
+    error_hook: Option<ErrorHook>,
+    gamepad_backend: Option<Box<dyn GamepadBackend>>,
+    fixed_clock: Option<Duration>,
+    replay: Option<InputRecording>,
+}
+
+impl AppBuilder {
+    /// Creates a new [`AppBuilder`].
+    pub fn new() -> Self {
+        #[cfg(debug_assertions)]
+        let _ = pretty_env_logger::formatted_builder()
+            .filter_level(log::LevelFilter::Debug)
+            .try_init();
+
+        Self::default()
+    }
+
+    /// Adds a plugin, skipping it if it's unique and a plugin with the same name was already
+    /// added.
+    pub fn add_plugin<P: Plugin>(&mut self, plugin: P) -> &mut Self {
+        self.add_boxed_plugin(Box::new(plugin))
+    }
+
+    /// Adds every enabled plugin of a group, in order.
+    pub fn add_plugins<G: PluginGroup>(&mut self, group: G) -> &mut Self {
+        for plugin in group.build().finish() {
+            self.add_boxed_plugin(plugin);
+        }
+
+        self
+    }
+
+    /// Checks if a plugin named `name` was added.
+    pub fn is_plugin_added(&self, name: &str) -> bool {
+        self.plugins.iter().any(|plugin| plugin == name)
+    }
+
+    /// Inserts a resource into the world.
+    pub fn insert_resource<R: Resource>(&mut self, resource: R) -> &mut Self {
+        self.world.insert_resource(resource);
+        self
+    }
+
+    /// Inserts the default resource `R` into the world if there isn't one yet.
+    pub fn init_resource<R: Resource + Default>(&mut self) -> &mut Self {
+        self.world.init_resource::<R>();
+        self
+    }
+
+    /// Configures the window created by the app, see [`WindowDescriptor`].
+    pub fn window(&mut self, descriptor: WindowDescriptor) -> &mut Self {
+        self.insert_resource(descriptor)
+    }
+
+    /// Sets the hook invoked with the error that stops the app, e.g. to save the game.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use log::error;
+    /// use woody::app::App;
+    /// use woody::app::Error;
+    /// use woody::ecs::world::World;
+    ///
+    /// fn report(world: &mut World, error: &Error) {
+    ///     error!("The game crashed with {} entities: {error}", world.stats().entities);
+    /// }
+    ///
+    /// if let Err(err) = App::builder().on_error(report).run() {
+    ///     eprintln!("{err}");
+    /// }
+    /// ```
+    pub fn on_error(&mut self, hook: ErrorHook) -> &mut Self {
+        self.error_hook = Some(hook);
+        self
+    }
+
+    /// Runs the app on a fixed clock, where every frame lasts `step` whatever the real time, e.g.
+    /// to record inputs which can be replayed identically.
+    ///
+    /// Note: headless apps use the clock given to [`AppBuilder::build_headless`] instead.
+    pub fn fixed_clock(&mut self, step: Duration) -> &mut Self {
+        self.fixed_clock = Some(step);
+        self
+    }
+
+    /// Replays the inputs of a recording on the frames they were recorded on, see
+    /// [`InputRecorder`](crate::input::replay::InputRecorder).
+    ///
+    /// Note: the app runs on a fixed clock at the frame time of the recording, headless apps
+    /// included, unless the recording is empty. A warning is logged if the recording wasn't made
+    /// on a fixed clock, as its frame time is then an average. The live inputs of the window and
+    /// gamepads are ignored until the replay is over.
+    pub fn replay(&mut self, recording: InputRecording) -> &mut Self {
+        if let Some(step) = recording.replay_step() {
+            self.fixed_clock = Some(step);
+        }
+        self.replay = Some(recording);
+        self
+    }
+
+    /// Sets where the gamepad inputs come from, see [`GamepadBackend`].
+    pub fn gamepad_backend(&mut self, backend: impl GamepadBackend + 'static) -> &mut Self {
+        self.gamepad_backend = Some(Box::new(backend));
+        self
+    }
+
+    /// Adds the state machine `S` starting at `initial`, see [`States`].
+    pub fn init_state<S: States>(&mut self, initial: S) -> &mut Self {
+        self.systems.init_state(&mut self.world, initial);
+        self
+    }
+
+    /// Creates the app along with its event loop, leaving this builder empty.
+    pub fn build(&mut self) -> Result<(App, EventLoop<()>), Error> {
+        let mut builder = std::mem::take(self);
+        builder.world.init_resource::<WindowDescriptor>();
+        input::check_resources(&builder.world);
+
+        let event_loop = EventLoop::new()?;
+        let descriptor = builder.world.resource::<WindowDescriptor>().unwrap();
+        let renderer = Renderer::new(&event_loop, descriptor)?;
+
+        // The window is created with the initial grab already.
+        let mut cursor = Cursor::default();
+        cursor.grab = descriptor.cursor_grab;
+        if !builder.world.contains_resource::<Cursor>() {
+            builder.world.insert_resource(cursor);
+        }
+        let state = GameState {
+            delta_time: 0.0,
+            last_time: 0.0,
+        };
+
+        #[cfg(feature = "gilrs")]
+        if builder.gamepad_backend.is_none() {
+            match gamepad::GilrsBackend::new() {
+                Ok(backend) => builder.gamepad_backend = Some(Box::new(backend)),
+                Err(err) => warn!("Gamepads are unavailable: {err}"),
+            }
+        }
+
+        Ok((
+            App {
+                world: builder.world,
+                systems: builder.systems,
+                renderer,
+                clock: Clock::new(builder.fixed_clock),
+                state,
+                error_hook: builder.error_hook,
+                modifiers: Modifiers::default(),
+                ime: Ime::default(),
+                cursor,
+                runner: FrameRunner::new(
+                    builder.gamepad_backend,
+                    builder.replay.map(InputReplay::new),
+                ),
+            },
+            event_loop,
+        ))
+    }
+
+    /// Creates an app without window nor renderer, leaving this builder empty.
+    ///
+    /// Note: the [`WindowDescriptor`] is ignored.
+    pub fn build_headless(&mut self, clock: HeadlessClock) -> HeadlessApp {
+        let mut builder = std::mem::take(self);
+        input::check_resources(&builder.world);
+
+        // Systems can still change the cursor settings, which only have an effect with a window.
+        builder.world.init_resource::<Cursor>();
+
+        let clock = match builder.replay {
+            Some(ref recording) if !recording.frame_time.is_zero() => {
+                HeadlessClock::Fixed(recording.frame_time)
+            }
+            _ => clock,
+        };
+
+        HeadlessApp::new(
+            builder.world,
+            builder.systems,
+            clock,
+            builder.gamepad_backend,
+            builder.replay.map(InputReplay::new),
+        )
+    }
+
+    /// Creates the app and runs it, see [`App::run`].
+    pub fn run(&mut self) -> Result<(), Error> {
+        let (app, event_loop) = self.build()?;
+
+        app.run(event_loop)
+    }
+
+    fn add_boxed_plugin(&mut self, plugin: Box<dyn Plugin>) -> &mut Self {
+        let name = plugin.name().to_owned();
+
+        if plugin.is_unique() && self.is_plugin_added(&name) {
+            warn!("Plugin `{name}` was already added, skipping it");
+            return self;
+        }
+
+        debug!("Adding plugin `{name}`");
+        self.plugins.push(name);
+        plugin.build(self);
+
+        self
     }
 }
 
 struct Clock {
     start: Instant,
-    elapsed: f64,
+    /// The duration of every frame if the clock is fixed.
+    step: Option<Duration>,
+    elapsed: Duration,
+    /// The duration of the last frame.
+    delta: Duration,
 }
 
 impl Clock {
-    fn new() -> Self {
+    fn new(step: Option<Duration>) -> Self {
         Self {
             start: Instant::now(),
-            elapsed: Duration::new(0, 0).as_secs_f64(),
+            step,
+            elapsed: Duration::ZERO,
+            delta: Duration::ZERO,
         }
     }
 
     fn start(&mut self) {
         self.start = Instant::now();
+        self.elapsed = Duration::ZERO;
     }
 
     fn update(&mut self) {
-        self.elapsed = self.start.elapsed().as_secs_f64();
+        let elapsed = match self.step {
+            Some(step) => self.elapsed + step,
+            None => self.start.elapsed(),
+        };
+
+        self.delta = elapsed - self.elapsed;
+        self.elapsed = elapsed;
     }
 }
 
 #[derive(Debug, Error)]
 pub enum Error {
-    //#[error("Could not create window: {0}")]
-    // Renderer(#[from] graphics::GraphicsError),
     #[error("event loop failed: {0}")]
     EventLoop(#[from] winit::error::EventLoopError),
+
+    #[error("renderer failed: {0}")]
+    Renderer(#[from] RendererError),
+
+    #[error("graphics backend failed: {0}")]
+    Graphics(#[from] GraphicsError),
 }
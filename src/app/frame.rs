@@ -0,0 +1,74 @@
+use std::time::Duration;
+
+use crate::app::GameState;
+use crate::ecs::world::World;
+use crate::event::UpdateEvent;
+use crate::input;
+use crate::input::gamepad;
+use crate::input::gamepad::GamepadBackend;
+use crate::input::replay;
+use crate::input::replay::InputReplay;
+use crate::systems::Systems;
+use crate::time;
+
+/// Runs the frames of both [`App`](super::App) and [`HeadlessApp`](super::headless::HeadlessApp),
+/// so they update the world the same way.
+pub(crate) struct FrameRunner {
+    gamepad_backend: Option<Box<dyn GamepadBackend>>,
+    replay: Option<InputReplay>,
+    /// The number of frames run so far.
+    frame: u64,
+}
+
+impl FrameRunner {
+    pub fn new(
+        gamepad_backend: Option<Box<dyn GamepadBackend>>,
+        replay: Option<InputReplay>,
+    ) -> Self {
+        Self {
+            gamepad_backend,
+            replay,
+            frame: 0,
+        }
+    }
+
+    /// Returns the number of frames run so far.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Checks if inputs are being replayed, in which case the live ones are ignored.
+    pub fn is_replaying(&self) -> bool {
+        self.replay
+            .as_ref()
+            .is_some_and(|replay| !replay.is_finished())
+    }
+
+    /// Runs a frame lasting `delta`: feeds the replayed and gamepad inputs, applies the state
+    /// transitions, then fires the [`UpdateEvent`].
+    pub fn run(
+        &mut self,
+        world: &mut World,
+        systems: &mut Systems,
+        state: GameState,
+        delta: Duration,
+    ) {
+        time::update(world, self.frame, delta);
+
+        // The gamepad inputs are left in the backend until the replay is over.
+        let replaying = self.is_replaying();
+        if let Some(replay) = &mut self.replay {
+            replay.update(world, systems, state, self.frame);
+        }
+        if let (Some(backend), false) = (&mut self.gamepad_backend, replaying) {
+            gamepad::poll(backend.as_mut(), world, systems, state);
+        }
+        systems.apply_state_transitions(world, state);
+        input::action::update(world);
+        systems.fire(world, state, UpdateEvent);
+        input::clear(world);
+        replay::end_frame(world, self.frame, delta);
+
+        self.frame += 1;
+    }
+}
@@ -0,0 +1,294 @@
+use std::time::Duration;
+
+use crate::app::frame::FrameRunner;
+use crate::app::GameState;
+use crate::ecs::world::World;
+use crate::event::take_exit;
+use crate::event::CreateEvent;
+use crate::event::ShutdownEvent;
+use crate::input::gamepad::GamepadBackend;
+use crate::input::replay::InputReplay;
+use crate::state::States;
+use crate::systems::Systems;
+
+/// How the time of a [`HeadlessApp`] moves forward on each frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HeadlessClock {
+    /// Every frame lasts the same duration.
+    Fixed(Duration),
+    /// Frames only last the time given to [`HeadlessApp::advance`].
+    Manual,
+}
+
+impl HeadlessClock {
+    /// Creates a fixed clock running `rate` frames per second.
+    pub fn fixed_rate(rate: f64) -> Self {
+        Self::Fixed(Duration::from_secs_f64(rate.recip()))
+    }
+}
+
+impl Default for HeadlessClock {
+    /// A fixed clock at 60 frames per second.
+    fn default() -> Self {
+        Self::fixed_rate(60.0)
+    }
+}
+
+/// An app without window nor renderer, which runs the same systems as [`App`](super::App) on
+/// demand, e.g. for servers, simulations and tests.
+///
+/// Example:
+/// ```no_run
+/// use woody::app::headless::HeadlessClock;
+/// use woody::app::App;
+/// use woody::app::GameState;
+/// use woody::ecs::world::World;
+/// use woody::event::UpdateEvent;
+///
+/// fn simulate(world: &mut World, state: GameState, _: UpdateEvent) {}
+///
+/// let mut builder = App::builder();
+/// builder.systems.subscribe(simulate);
+///
+/// let mut app = builder.build_headless(HeadlessClock::fixed_rate(20.0));
+/// app.run_for(60);
+///
+/// assert_eq!(app.frame(), 60);
+/// assert_eq!(app.elapsed().as_secs(), 3);
+/// ```
+pub struct HeadlessApp {
+    pub world: World,
+    pub systems: Systems,
+    state: GameState,
+    clock: HeadlessClock,
+    /// The time added to the next frame with [`HeadlessApp::advance`].
+    pending: Duration,
+    elapsed: Duration,
+    exited: bool,
+    runner: FrameRunner,
+}
+
+impl HeadlessApp {
+    pub(super) fn new(
+        world: World,
+        systems: Systems,
+        clock: HeadlessClock,
+        gamepad_backend: Option<Box<dyn GamepadBackend>>,
+        replay: Option<InputReplay>,
+    ) -> Self {
+        Self {
+            world,
+            systems,
+            state: GameState {
+                delta_time: 0.0,
+                last_time: 0.0,
+            },
+            clock,
+            pending: Duration::ZERO,
+            elapsed: Duration::ZERO,
+            exited: false,
+            runner: FrameRunner::new(gamepad_backend, replay),
+        }
+    }
+
+    /// Adds the state machine `S` starting at `initial`, see [`States`].
+    pub fn init_state<S: States>(&mut self, initial: S) {
+        self.systems.init_state(&mut self.world, initial);
+    }
+
+    /// Adds `delta` to the duration of the next frame, which is how a manual clock moves.
+    pub fn advance(&mut self, delta: Duration) {
+        self.pending += delta;
+    }
+
+    /// Runs a single frame, firing the [`CreateEvent`] first if it's the first one.
+    ///
+    /// Note: once [`AppExit`](crate::event::AppExit) is sent, it's fired at the end of the frame
+    /// along with the [`ShutdownEvent`] and no more frames run.
+    pub fn update(&mut self) {
+        if self.exited {
+            return;
+        }
+
+        if self.runner.frame() == 0 {
+            self.systems.fire(&mut self.world, self.state, CreateEvent);
+            self.systems
+                .apply_state_transitions(&mut self.world, self.state);
+        }
+
+        let delta = match self.clock {
+            HeadlessClock::Fixed(step) => step,
+            HeadlessClock::Manual => Duration::ZERO,
+        } + std::mem::take(&mut self.pending);
+
+        self.elapsed += delta;
+        self.state.delta_time = delta.as_secs_f64();
+
+        self.runner
+            .run(&mut self.world, &mut self.systems, self.state, delta);
+
+        self.state.last_time = self.elapsed.as_secs_f64();
+
+        if let Some(exit) = take_exit(&mut self.world) {
+            self.systems.fire(&mut self.world, self.state, exit);
+            self.systems
+                .fire(&mut self.world, self.state, ShutdownEvent);
+            self.exited = true;
+        }
+    }
+
+    /// Runs `frames` frames in a row, stopping early if the app exits.
+    pub fn run_for(&mut self, frames: u64) {
+        for _ in 0..frames {
+            if self.exited {
+                break;
+            }
+
+            self.update();
+        }
+    }
+
+    /// Checks if the app exited through [`AppExit`](crate::event::AppExit).
+    pub fn has_exited(&self) -> bool {
+        self.exited
+    }
+
+    /// Returns the number of frames run so far.
+    pub fn frame(&self) -> u64 {
+        self.runner.frame()
+    }
+
+    /// Returns the time elapsed on the clock so far.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Returns the state passed to the systems on the last frame.
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::app::AppBuilder;
+    use crate::condition::on_timer;
+    use crate::ecs::resource::Resource;
+    use crate::event::AppExit;
+    use crate::event::UpdateEvent;
+    use crate::window::Cursor;
+    use crate::window::CursorGrab;
+
+    #[derive(Debug, Default)]
+    struct Counters {
+        created: u32,
+        updated: u32,
+        ticked: u32,
+    }
+
+    impl Resource for Counters {}
+
+    fn create(world: &mut World, _: GameState, _: CreateEvent) {
+        world.resource_mut::<Counters>().unwrap().created += 1;
+    }
+
+    fn update(world: &mut World, _: GameState, _: UpdateEvent) {
+        world.resource_mut::<Counters>().unwrap().updated += 1;
+    }
+
+    fn tick(world: &mut World, _: GameState, _: UpdateEvent) {
+        world.resource_mut::<Counters>().unwrap().ticked += 1;
+    }
+
+    fn builder() -> AppBuilder {
+        let mut builder = AppBuilder::new();
+        builder.init_resource::<Counters>();
+        builder.systems.subscribe(create);
+        builder.systems.subscribe(update);
+        builder
+            .systems
+            .subscribe(tick)
+            .run_if(on_timer(Duration::from_secs(1)));
+        builder
+    }
+
+    #[test]
+    fn fixed_clock() {
+        let mut app = builder().build_headless(HeadlessClock::fixed_rate(4.0));
+        app.run_for(10);
+
+        let counters = app.world.resource::<Counters>().unwrap();
+        assert_eq!(counters.created, 1);
+        assert_eq!(counters.updated, 10);
+        assert_eq!(counters.ticked, 2);
+        assert_eq!(app.frame(), 10);
+        assert_eq!(app.elapsed(), Duration::from_millis(2500));
+        assert_eq!(app.state().delta_time, 0.25);
+    }
+
+    #[test]
+    fn manual_clock() {
+        let mut app = builder().build_headless(HeadlessClock::Manual);
+        app.run_for(3);
+        assert_eq!(app.elapsed(), Duration::ZERO);
+        assert_eq!(app.world.resource::<Counters>().unwrap().ticked, 0);
+
+        app.advance(Duration::from_millis(1500));
+        app.update();
+        assert_eq!(app.elapsed(), Duration::from_millis(1500));
+        assert_eq!(app.state().delta_time, 1.5);
+        assert_eq!(app.world.resource::<Counters>().unwrap().ticked, 1);
+
+        // The advanced time is only used once.
+        app.update();
+        assert_eq!(app.state().delta_time, 0.0);
+    }
+
+    #[test]
+    fn cursor() {
+        fn lock(world: &mut World, _: GameState, _: UpdateEvent) {
+            world.resource_mut::<Cursor>().unwrap().lock();
+        }
+
+        let mut builder = builder();
+        builder.systems.subscribe(lock);
+
+        let mut app = builder.build_headless(HeadlessClock::default());
+        app.update();
+
+        assert_eq!(
+            app.world.resource::<Cursor>().unwrap().grab,
+            CursorGrab::Locked
+        );
+    }
+
+    #[test]
+    fn exit() {
+        fn quit(world: &mut World, _: GameState, _: UpdateEvent) {
+            if world.resource::<Counters>().unwrap().updated == 3 {
+                AppExit.send(world);
+            }
+        }
+
+        fn exit(world: &mut World, _: GameState, _: AppExit) {
+            world.resource_mut::<Counters>().unwrap().created += 100;
+        }
+
+        fn shutdown(world: &mut World, _: GameState, _: ShutdownEvent) {
+            world.resource_mut::<Counters>().unwrap().created += 10;
+        }
+
+        let mut builder = builder();
+        builder.systems.subscribe(quit);
+        builder.systems.subscribe(exit);
+        builder.systems.subscribe(shutdown);
+
+        let mut app = builder.build_headless(HeadlessClock::default());
+        app.run_for(10);
+
+        assert!(app.has_exited());
+        assert_eq!(app.frame(), 3);
+        assert_eq!(app.world.resource::<Counters>().unwrap().created, 111);
+    }
+}
@@ -0,0 +1,218 @@
+use std::time::Duration;
+
+use crate::app::GameState;
+use crate::ecs::component::Component;
+use crate::ecs::resource::Resource;
+use crate::ecs::world::World;
+
+/// A check deciding whether a system runs when its event fires, see
+/// [`SystemConfig::run_if`](crate::event::SystemConfig::run_if).
+///
+/// It's implemented for every `FnMut(&World, GameState) -> bool` closure, so conditions can keep
+/// their own state between evaluations, and they can be combined with [`Condition::and`],
+/// [`Condition::or`] and [`not`].
+///
+/// Example:
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use woody::app::App;
+/// use woody::app::GameState;
+/// use woody::condition::any_with_component;
+/// use woody::condition::not;
+/// use woody::condition::on_timer;
+/// use woody::condition::resource_exists;
+/// use woody::condition::Condition;
+/// use woody::ecs::component::Component;
+/// use woody::ecs::resource::Resource;
+/// use woody::ecs::world::World;
+/// use woody::event::UpdateEvent;
+///
+/// #[derive(Component)]
+/// struct Enemy;
+///
+/// #[derive(Resource)]
+/// struct Paused;
+///
+/// fn spawn_wave(world: &mut World, _: GameState, _: UpdateEvent) {}
+///
+/// let (mut app, event_loop) = App::new().unwrap();
+///
+/// app.systems.subscribe(spawn_wave).run_if(
+///     on_timer(Duration::from_secs(30))
+///         .or(not(any_with_component::<Enemy>()))
+///         .and(not(resource_exists::<Paused>())),
+/// );
+///
+/// app.run(event_loop).unwrap();
+/// ```
+pub trait Condition: 'static {
+    fn evaluate(&mut self, world: &World, state: GameState) -> bool;
+
+    /// Holds when both conditions hold.
+    ///
+    /// Note: `other` isn't evaluated if this condition doesn't hold, so a timer there doesn't
+    /// advance.
+    fn and<C: Condition>(self, other: C) -> And<Self, C>
+    where
+        Self: Sized,
+    {
+        And(self, other)
+    }
+
+    /// Holds when any of the conditions holds.
+    ///
+    /// Note: `other` isn't evaluated if this condition holds, so a timer there doesn't advance.
+    fn or<C: Condition>(self, other: C) -> Or<Self, C>
+    where
+        Self: Sized,
+    {
+        Or(self, other)
+    }
+}
+
+impl<F> Condition for F
+where
+    F: FnMut(&World, GameState) -> bool + 'static,
+{
+    fn evaluate(&mut self, world: &World, state: GameState) -> bool {
+        self(world, state)
+    }
+}
+
+/// Both conditions, see [`Condition::and`].
+#[derive(Debug, Clone, Copy)]
+pub struct And<A, B>(A, B);
+
+impl<A: Condition, B: Condition> Condition for And<A, B> {
+    fn evaluate(&mut self, world: &World, state: GameState) -> bool {
+        self.0.evaluate(world, state) && self.1.evaluate(world, state)
+    }
+}
+
+/// Any of the conditions, see [`Condition::or`].
+#[derive(Debug, Clone, Copy)]
+pub struct Or<A, B>(A, B);
+
+impl<A: Condition, B: Condition> Condition for Or<A, B> {
+    fn evaluate(&mut self, world: &World, state: GameState) -> bool {
+        self.0.evaluate(world, state) || self.1.evaluate(world, state)
+    }
+}
+
+/// The negated condition, see [`not`].
+#[derive(Debug, Clone, Copy)]
+pub struct Not<C>(C);
+
+impl<C: Condition> Condition for Not<C> {
+    fn evaluate(&mut self, world: &World, state: GameState) -> bool {
+        !self.0.evaluate(world, state)
+    }
+}
+
+/// Holds when `condition` doesn't.
+pub fn not<C: Condition>(condition: C) -> Not<C> {
+    Not(condition)
+}
+
+/// Holds while there is a resource `R`.
+pub fn resource_exists<R: Resource>() -> impl Condition {
+    |world: &World, _: GameState| world.contains_resource::<R>()
+}
+
+/// Holds while there is at least one entity with the component `T`.
+pub fn any_with_component<T: Component>() -> impl Condition {
+    |world: &World, _: GameState| world.query_matches::<&T>() > 0
+}
+
+/// Holds once every time `duration` elapses, measured with the
+/// [`delta_time`](GameState::delta_time) of the events the system handles.
+///
+/// Note: the time only advances when the condition is evaluated, so use it on systems handling
+/// [`UpdateEvent`](crate::event::UpdateEvent).
+pub fn on_timer(duration: Duration) -> impl Condition {
+    OnTimer {
+        duration: duration.as_secs_f64(),
+        elapsed: 0.0,
+    }
+}
+
+/// The condition created by [`on_timer`].
+struct OnTimer {
+    duration: f64,
+    elapsed: f64,
+}
+
+impl Condition for OnTimer {
+    fn evaluate(&mut self, _: &World, state: GameState) -> bool {
+        self.elapsed += state.delta_time;
+
+        if self.elapsed < self.duration {
+            return false;
+        }
+
+        // Keep the remainder so the timer doesn't drift.
+        self.elapsed -= self.duration;
+        true
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+
+    struct Enemy;
+    impl Component for Enemy {}
+
+    struct Paused;
+    impl Resource for Paused {}
+
+    fn frame(delta_time: f64) -> GameState {
+        GameState {
+            delta_time,
+            last_time: 0.0,
+        }
+    }
+
+    #[test]
+    fn conditions() {
+        let mut world = World::new();
+        let state = frame(0.0);
+
+        let mut paused = resource_exists::<Paused>();
+        let mut enemies = any_with_component::<Enemy>();
+        assert!(!paused.evaluate(&world, state));
+        assert!(!enemies.evaluate(&world, state));
+
+        world.insert_resource(Paused);
+        let enemy = world.spawn(Enemy);
+        assert!(paused.evaluate(&world, state));
+        assert!(enemies.evaluate(&world, state));
+
+        let mut combined = any_with_component::<Enemy>().and(not(resource_exists::<Paused>()));
+        assert!(!combined.evaluate(&world, state));
+
+        world.remove_resource::<Paused>();
+        assert!(combined.evaluate(&world, state));
+
+        world.despawn(enemy).unwrap();
+        assert!(!combined.evaluate(&world, state));
+        assert!(combined
+            .or(|_: &World, _: GameState| true)
+            .evaluate(&world, state));
+    }
+
+    #[test]
+    fn timer() {
+        let world = World::new();
+        let mut timer = on_timer(Duration::from_millis(100));
+
+        assert!(!timer.evaluate(&world, frame(0.06)));
+        assert!(timer.evaluate(&world, frame(0.06)));
+        // The 20ms left over count towards the next tick.
+        assert!(!timer.evaluate(&world, frame(0.06)));
+        assert!(timer.evaluate(&world, frame(0.03)));
+    }
+}
@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use log::debug;
+
+use crate::app::AppBuilder;
+use crate::app::GameState;
+use crate::condition::on_timer;
+use crate::ecs::world::World;
+use crate::event::UpdateEvent;
+use crate::plugin::Plugin;
+
+/// Logs the [`WorldStats`](crate::ecs::stats::WorldStats) at debug level every `interval`.
+#[derive(Debug, Clone, Copy)]
+pub struct DiagnosticsPlugin {
+    pub interval: Duration,
+}
+
+impl Default for DiagnosticsPlugin {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(10),
+        }
+    }
+}
+
+impl Plugin for DiagnosticsPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.systems
+            .subscribe(log_stats)
+            .run_if(on_timer(self.interval));
+    }
+}
+
+fn log_stats(world: &mut World, _: GameState, _: UpdateEvent) {
+    debug!("{}", world.stats());
+}
@@ -55,8 +55,7 @@ impl Archetypes {
     /// Gets a mutable archetype reference but unwraps the value from Option.
     ///
     /// SAFETY: you must guarantee that the archetype of the specified id already exists.
-    #[allow(dead_code)]
-    unsafe fn archetype_mut_unchecked(&mut self, id: ArchetypeId) -> FxRefMut<'_, ArchetypeId, Archetype> {
+    pub(super) unsafe fn archetype_mut_unchecked(&mut self, id: ArchetypeId) -> FxRefMut<'_, ArchetypeId, Archetype> {
         self.0.get_mut(&id).unwrap_unchecked()
     }
 
@@ -79,10 +78,90 @@ impl Archetypes {
     }
 
     /// Itarates over Archetypes.
-    pub(super) fn iter(&self) -> impl ParallelIterator<Item = FxRefMulti<ArchetypeId, Archetype>> {
+    pub(super) fn iter(&self) -> impl ParallelIterator<Item = FxRefMulti<'_, ArchetypeId, Archetype>> {
         self.0.par_iter()
     }
 
+    /// Returns the archetype reached by adding or removing `c_type` from the archetype `from`.
+    ///
+    /// The cached edge of `from` is followed if there's one, otherwise the archetype is created if
+    /// needed and the edges between both archetypes are cached.
+    pub(super) fn transition(&mut self, from: ArchetypeId, c_type: ComponentType, kind: EdgeKind) -> ArchetypeId {
+        let c_types = {
+            let Some(archetype) = self.0.get(&from) else {
+                return from;
+            };
+
+            if let Some(to) = archetype.edge(c_type, kind) {
+                return to;
+            }
+
+            let mut c_types = archetype.c_types.clone();
+            match kind {
+                EdgeKind::Add if !c_types.contains(&c_type) => c_types.push(c_type),
+                EdgeKind::Remove if c_types.contains(&c_type) => c_types.retain(|&ct| ct != c_type),
+                // Nothing changes so there's no edge to cache.
+                _ => return from,
+            }
+
+            c_types
+        };
+
+        let to = Archetype::id_from_c_types(&c_types);
+
+        self.insert(to, &c_types).set_edge(c_type, kind.opposite(), from);
+        if let Some(mut archetype) = self.0.get_mut(&from) {
+            archetype.set_edge(c_type, kind, to);
+        }
+
+        to
+    }
+
+    /// Returns the archetype reached by adding or removing every component type in `c_types` from
+    /// the archetype `from`, see [`Archetypes::transition`].
+    pub(super) fn transition_many(&mut self, from: ArchetypeId, c_types: &[ComponentType], kind: EdgeKind) -> ArchetypeId {
+        c_types
+            .iter()
+            .fold(from, |id, &c_type| self.transition(id, c_type, kind))
+    }
+
+    /// Drops the archetypes without entities and clears the cached edges since they may point to
+    /// the dropped ones, returns how many archetypes were dropped.
+    pub(super) fn remove_empty(&mut self) -> usize {
+        let len = self.len();
+
+        self.0.retain(|_, archetype| {
+            archetype.edges.clear();
+            !archetype.entities.is_empty()
+        });
+
+        len - self.len()
+    }
+}
+
+/// Whether an [`ArchetypeEdge`] adds or removes a component type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(super) enum EdgeKind {
+    Add,
+    Remove,
+}
+
+impl EdgeKind {
+    fn opposite(self) -> Self {
+        match self {
+            Self::Add => Self::Remove,
+            Self::Remove => Self::Add,
+        }
+    }
+}
+
+/// The archetypes reached by adding or removing a single component type, cached so entities can
+/// move between archetypes without recomputing them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct ArchetypeEdge {
+    c_type: ComponentType,
+    add: Option<ArchetypeId>,
+    remove: Option<ArchetypeId>,
 }
 
 /// Unique archetype identifier which is created from the component type list.
@@ -98,6 +177,8 @@ pub struct Archetype {
     entities: Vec<usize>,
     /// Which components this archetype has.
     c_types: Vec<ComponentType>,
+    /// The cached transitions to other archetypes.
+    edges: Vec<ArchetypeEdge>,
 }
 
 impl Archetype {
@@ -105,11 +186,22 @@ impl Archetype {
     pub(super) fn new(c_types: Vec<ComponentType>) -> Self {
         Self {
             id: Self::id_from_c_types(&c_types),
-            entities: Vec::with_capacity(10_000),
+            entities: Vec::new(),
             c_types,
+            edges: Vec::new(),
         }
     }
 
+    /// Returns the archetype id.
+    pub fn id(&self) -> ArchetypeId {
+        self.id
+    }
+
+    /// Returns the component types of this archetype.
+    pub fn components_types(&self) -> &[ComponentType] {
+        &self.c_types
+    }
+
     /// Returns a reference to this archetype entities.
     pub(super) fn entities(&self) -> &Vec<usize> {
         &self.entities
@@ -131,6 +223,32 @@ impl Archetype {
             .fold(0_u128, |acc, id| acc.saturating_add(id))
     }
 
+    /// Returns the archetype reached by adding or removing `c_type`, if the edge is cached.
+    fn edge(&self, c_type: ComponentType, kind: EdgeKind) -> Option<ArchetypeId> {
+        let edge = self.edges.iter().find(|edge| edge.c_type == c_type)?;
+
+        match kind {
+            EdgeKind::Add => edge.add,
+            EdgeKind::Remove => edge.remove,
+        }
+    }
+
+    /// Caches the archetype reached by adding or removing `c_type`.
+    fn set_edge(&mut self, c_type: ComponentType, kind: EdgeKind, to: ArchetypeId) {
+        let edge = match self.edges.iter().position(|edge| edge.c_type == c_type) {
+            Some(index) => &mut self.edges[index],
+            None => {
+                self.edges.push(ArchetypeEdge { c_type, add: None, remove: None });
+                self.edges.last_mut().unwrap()
+            }
+        };
+
+        match kind {
+            EdgeKind::Add => edge.add = Some(to),
+            EdgeKind::Remove => edge.remove = Some(to),
+        }
+    }
+
     /// Checks if this archetype contains certain component types.
     pub(super) fn contains_c_types(&self, c_types: &[ComponentType]) -> bool {
         c_types.par_iter().all(|c_type| self.c_types.contains(c_type))
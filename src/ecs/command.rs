@@ -0,0 +1,77 @@
+use super::component::Bundle;
+use super::entity::EntityId;
+use super::world::World;
+
+type Command = Box<dyn FnOnce(&mut World) + Send>;
+
+/// A queue of operations that will be applied to the [`World`] later.
+///
+/// It's used where the world can't be mutated right away, e.g. inside a
+/// [`ComponentHook`](super::hook::ComponentHook).
+///
+/// Note: errors of the queued operations (e.g. inserting into a despawned entity) are ignored.
+#[derive(Default)]
+pub struct Commands {
+    queue: Vec<Command>,
+}
+
+impl Commands {
+    /// Queues the spawn of an entity.
+    pub fn spawn<B: Bundle + Send>(&mut self, bundle: B) {
+        self.add(move |world| {
+            world.spawn(bundle);
+        });
+    }
+
+    /// Queues the insertion of components into an entity.
+    pub fn insert<B: Bundle + Send>(&mut self, entity: EntityId, bundle: B) {
+        self.add(move |world| {
+            let _ = world.insert(entity, bundle);
+        });
+    }
+
+    /// Queues the removal of components from an entity.
+    pub fn remove<B: Bundle>(&mut self, entity: EntityId) {
+        self.add(move |world| {
+            let _ = world.remove::<B>(entity);
+        });
+    }
+
+    /// Queues the despawn of an entity.
+    pub fn despawn(&mut self, entity: EntityId) {
+        self.add(move |world| {
+            let _ = world.despawn(entity);
+        });
+    }
+
+    /// Queues a custom operation.
+    pub fn add(&mut self, command: impl FnOnce(&mut World) + Send + 'static) {
+        self.queue.push(Box::new(command));
+    }
+
+    /// Returns how many commands are queued.
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Checks if its empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Applies every queued command to the world in the order they were queued.
+    pub fn apply(self, world: &mut World) {
+        for command in self.queue {
+            command(world);
+        }
+    }
+}
+
+impl std::fmt::Debug for Commands {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Commands")
+            .field("len", &self.len())
+            .finish()
+    }
+}
@@ -0,0 +1,129 @@
+use rayon::iter::ParallelIterator;
+use rustc_hash::FxHashMap;
+
+use super::component::ComponentPtr;
+use super::component::ComponentType;
+use super::world::World;
+
+/// What a [`World::compact`] pass dropped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Compaction {
+    /// How many archetypes without entities were dropped.
+    pub archetypes: usize,
+    /// How many empty component slots were dropped.
+    pub slots: usize,
+}
+
+impl World {
+    /// Drops the archetypes without entities and defragments the component storages, moving the
+    /// components of each archetype next to each other and dropping the slots left empty by
+    /// removed components.
+    ///
+    /// Nothing is compacted automatically, call it after despawning or moving around lots of
+    /// entities, e.g. when a level is unloaded.
+    pub fn compact(&mut self) -> Compaction {
+        let archetypes = self.archetypes.remove_empty();
+
+        let c_types: Vec<ComponentType> = self
+            .components
+            .iter()
+            .map(|storage| *storage.key())
+            .collect();
+
+        let mut slots: FxHashMap<ComponentType, Vec<Option<ComponentPtr>>> = FxHashMap::default();
+        let mut previous_slots = 0;
+
+        for c_type in c_types {
+            if let Some(mut storage) = self.components.storage_mut(c_type) {
+                let taken = storage.take_slots();
+                previous_slots += taken.len();
+                slots.insert(c_type, taken);
+            }
+        }
+
+        // Archetype by archetype, so queries go through the storages in order.
+        let order: Vec<usize> = self
+            .archetypes
+            .iter()
+            .flat_map_iter(|archetype| archetype.entities().clone())
+            .collect();
+
+        let mut current_slots = 0;
+
+        for index in order {
+            let Some(Some(entity)) = self.entities.get_mut(index) else {
+                continue;
+            };
+
+            for (c_type, c_ptr) in entity.c_ptrs_mut() {
+                let slot = slots
+                    .get_mut(c_type)
+                    .and_then(|slots| slots.get_mut(*c_ptr))
+                    .and_then(Option::take);
+
+                if let Some(mut storage) = self.components.storage_mut(*c_type) {
+                    *c_ptr = storage.push_slot(slot);
+                    current_slots += 1;
+                }
+            }
+        }
+
+        Compaction {
+            archetypes,
+            slots: previous_slots - current_slots,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecs::component::Component;
+
+    #[derive(Debug)]
+    struct Position(u8);
+    impl Component for Position {}
+
+    #[derive(Debug)]
+    struct Velocity(u8);
+    impl Component for Velocity {}
+
+    #[test]
+    fn compact() {
+        let mut world = World::new();
+
+        let entities = world.spawn_batch((0..10).map(|i| (Position(i), Velocity(i))));
+        let moving = world.spawn(Position(10));
+        world.insert(moving, Velocity(10)).unwrap();
+        world.remove::<Velocity>(moving).unwrap();
+        world.insert(moving, Velocity(10)).unwrap();
+
+        for &entity in &entities[..5] {
+            world.despawn(entity).unwrap();
+        }
+        world.despawn(moving).unwrap();
+
+        let compaction = world.compact();
+        assert_eq!(compaction.archetypes, 1);
+        // 6 despawned, plus the 1 left behind by re-inserting the velocity.
+        assert_eq!(compaction.slots, 13);
+        assert_eq!(world.compact(), Compaction::default());
+
+        let stats = world.stats();
+        assert_eq!(stats.archetypes.len(), 1);
+        assert!(stats
+            .storages
+            .iter()
+            .all(|storage| storage.slots == storage.components && storage.components == 5));
+
+        for (i, &entity) in entities.iter().enumerate().skip(5) {
+            assert_eq!(world.get::<Position>(entity).unwrap().0, i as u8);
+            assert_eq!(world.get::<Velocity>(entity).unwrap().0, i as u8);
+        }
+
+        // Edges are cached again after compacting.
+        world.remove::<Velocity>(entities[5]).unwrap();
+        world.insert(entities[5], Velocity(5)).unwrap();
+        assert_eq!(world.query::<(&Position, &Velocity)>().len(), 5);
+    }
+}
@@ -1,6 +1,7 @@
 use std::any::Any;
 use std::any::TypeId;
 use std::hash::BuildHasherDefault;
+use std::marker::PhantomData;
 
 use dashmap::DashMap;
 use rayon::iter::IntoParallelRefIterator;
@@ -8,7 +9,9 @@ use rayon::iter::ParallelIterator;
 use rayon::prelude::IntoParallelRefMutIterator;
 use rustc_hash::FxHasher;
 
+use super::hook::ComponentHooks;
 use super::FxRef;
+use super::FxRefMulti;
 use super::FxRefMut;
 
 pub use ecs_macros::Component;
@@ -17,11 +20,23 @@ pub trait Component: Send + Sync + Any {
     fn component_type() -> ComponentType {
         TypeId::of::<Self>()
     }
+
+    /// Registers the hooks every component of this type needs, it's invoked once when the
+    /// storage of this component is created.
+    fn register_hooks(_hooks: &mut ComponentHooks) {}
 }
 
 pub trait Bundle: 'static {
     fn components_types() -> Vec<ComponentType>;
 
+    /// Creates a [`ComponentStorage`] for each component of this bundle that doesn't have one yet,
+    /// registering the component hooks along with it.
+    fn register_components(storages: &mut Components, hooks: &mut ComponentHooks);
+
+    /// Stores the components of this bundle into their storages.
+    ///
+    /// Components whose type is already in `component_indexes` replace the value at that index,
+    /// the others are pushed and their index appended to `component_indexes`.
     fn store_components(
         self,
         storages: &mut Components,
@@ -42,12 +57,24 @@ impl Components {
         ))
     }
 
-    /// Returns how many components we have.
-    #[allow(dead_code)]
-    pub(super) fn len(&self) -> usize {
+    /// Returns how many component storages we have.
+    pub fn len(&self) -> usize {
         self.0.len()
     }
 
+    /// Checks if its empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Iterates over the component storages.
+    pub(super) fn iter(
+        &self,
+    ) -> impl Iterator<Item = FxRefMulti<'_, ComponentType, ComponentStorage>> {
+        self.0.iter()
+    }
+
     /// Gets a component storage reference for the [`ComponentType`].
     #[allow(dead_code)]
     pub(super) fn storage(
@@ -58,6 +85,10 @@ impl Components {
     }
 
     /// Gets a storage reference but unwraps the value from Option.
+    ///
+    /// # Safety
+    ///
+    /// The storage for the [`ComponentType`] must exist.
     pub(super) unsafe fn storage_unchecked(
         &self,
         c_type: ComponentType,
@@ -75,33 +106,73 @@ impl Components {
     }
 
     /// Gets a mutable storage reference but unwraps the value from Option.
+    ///
+    /// # Safety
+    ///
+    /// The storage for the [`ComponentType`] must exist.
     pub(super) unsafe fn storage_mut_unchecked(
-        &self,
+        &mut self,
         c_type: ComponentType,
     ) -> FxRefMut<'_, ComponentType, ComponentStorage> {
         self.0.get_mut(&c_type).unwrap_unchecked()
     }
 
-    /// Inserts a new storage into the map and a mutable reference to it. If a storage already
-    /// exists returns it.
-    pub(super) fn insert(
-        &mut self,
-        c_type: ComponentType,
-    ) -> FxRefMut<'_, ComponentType, ComponentStorage> {
-        if self.0.contains_key(&c_type) {
-            return unsafe { self.storage_mut_unchecked(c_type) };
+    /// Inserts a new storage for component `T` into the map and a mutable reference to it. If a
+    /// storage already exists returns it.
+    pub(super) fn insert<T: Component>(&mut self) -> FxRefMut<'_, ComponentType, ComponentStorage> {
+        if self.0.contains_key(&T::component_type()) {
+            return unsafe { self.storage_mut_unchecked(T::component_type()) };
         }
 
-        unsafe { self.insert_unchecked(c_type) }
+        unsafe { self.insert_unchecked::<T>() }
     }
 
-    /// Inserts a new storage into the map but ignores if the entry already exists.
-    unsafe fn insert_unchecked(
+    /// Inserts a new storage for component `T` into the map but ignores if the entry already
+    /// exists.
+    unsafe fn insert_unchecked<T: Component>(
         &mut self,
-        c_type: ComponentType,
     ) -> FxRefMut<'_, ComponentType, ComponentStorage> {
-        self.0.insert(c_type, ComponentStorage::from_c_type(c_type));
-        self.storage_mut_unchecked(c_type)
+        self.0
+            .insert(T::component_type(), ComponentStorage::new::<T>());
+        self.storage_mut_unchecked(T::component_type())
+    }
+
+    /// Gets a read only [`Column`] of the storage for the [`ComponentType`] without keeping the
+    /// storage locked.
+    ///
+    /// # Safety
+    ///
+    /// The storage must exist, and it must not be modified nor mutably accessed, e.g. through a
+    /// [`ColumnMut`], for as long as the column lives.
+    pub(super) unsafe fn column_unchecked(&self, c_type: ComponentType) -> Column<'_> {
+        let slots: *const [Option<ComponentPtr>] = self.storage_unchecked(c_type).ptrs.as_slice();
+
+        Column(&*slots)
+    }
+
+    /// Gets a [`ColumnMut`] of the storage for the [`ComponentType`] without keeping the storage
+    /// locked.
+    ///
+    /// Note: it only takes `&self` so a query can get a column for each of its components, the
+    /// storage is locked while the column is created but not afterwards.
+    ///
+    /// # Safety
+    ///
+    /// The storage must exist, and it must not be modified nor accessed by anything else, e.g.
+    /// another [`Column`] or [`ColumnMut`] of the same storage, for as long as the column lives.
+    pub(super) unsafe fn column_mut_unchecked(&self, c_type: ComponentType) -> ColumnMut<'_> {
+        let mut storage = self.0.get_mut(&c_type).unwrap_unchecked();
+
+        ColumnMut {
+            ptr: storage.ptrs.as_mut_ptr(),
+            len: storage.ptrs.len(),
+            marker: PhantomData,
+        }
+    }
+
+    /// Returns the type name of the component stored under [`ComponentType`].
+    pub(super) fn name(&self, c_type: ComponentType) -> Option<&'static str> {
+        self.0.get(&c_type).map(|storage| storage.name())
     }
 }
 
@@ -136,33 +207,120 @@ impl ComponentPtr {
     }
 }
 
-/// SAFETY: there's any.
+/// SAFETY: the boxed value is always a [`Component`], which is `Send`.
 unsafe impl Send for ComponentPtr {}
 
-/// SAFETY: there's any.
+/// SAFETY: the boxed value is always a [`Component`], which is `Sync`.
 unsafe impl Sync for ComponentPtr {}
 
+/// A read only view over the component slots of a [`ComponentStorage`].
+#[derive(Debug, Clone, Copy)]
+pub struct Column<'a>(&'a [Option<ComponentPtr>]);
+
+impl<'a> Column<'a> {
+    /// Gets a component reference from the specified index but unwraps.
+    ///
+    /// SAFETY: you must know that the index is valid and the slot holds a component of type `T`.
+    pub(super) unsafe fn get_unchecked<T: Component>(self, index: usize) -> &'a T {
+        self.0
+            .get_unchecked(index)
+            .as_ref()
+            .unwrap_unchecked()
+            .cast_ref::<T>()
+            .unwrap_unchecked()
+    }
+}
+
+/// A mutable view over the component slots of a [`ComponentStorage`].
+///
+/// Unlike `&mut [T]` it can be copied, so each item of a query (even on different threads) can
+/// get its own component out of it. That's sound as long as every item points to a different
+/// slot, which is always the case since an entity only has one slot per storage.
+#[derive(Debug, Clone, Copy)]
+pub struct ColumnMut<'a> {
+    ptr: *mut Option<ComponentPtr>,
+    len: usize,
+    marker: PhantomData<&'a mut [Option<ComponentPtr>]>,
+}
+
+/// SAFETY: a ColumnMut behaves like a `&mut [Option<ComponentPtr>]`, which is `Send` since
+/// [`ComponentPtr`] is.
+unsafe impl Send for ColumnMut<'_> {}
+
+/// SAFETY: sharing a ColumnMut only allows copying it, the slots can only be reached through
+/// [`ColumnMut::get_unchecked`] whose contract forbids two live references to the same slot.
+unsafe impl Sync for ColumnMut<'_> {}
+
+impl<'a> ColumnMut<'a> {
+    /// Gets a mutable reference to component from the specified index but unwraps.
+    ///
+    /// SAFETY: you must know that the index is valid and the slot holds a component of type `T`,
+    /// and that no other reference to the same slot is alive for `'a`.
+    pub(super) unsafe fn get_unchecked<T: Component>(self, index: usize) -> &'a mut T {
+        debug_assert!(index < self.len);
+
+        (*self.ptr.add(index))
+            .as_mut()
+            .unwrap_unchecked()
+            .cast_mut::<T>()
+            .unwrap_unchecked()
+    }
+}
+
 #[derive(Debug)]
 pub struct ComponentStorage {
     c_type: ComponentType,
-    ptrs: Vec<ComponentPtr>,
+    /// The component type name, used for debugging.
+    name: &'static str,
+    /// The size of a single component in bytes.
+    size: usize,
+    /// Component slots, a slot is `None` after its component has been removed.
+    ptrs: Vec<Option<ComponentPtr>>,
 }
 
 impl ComponentStorage {
     /// Creates a new [ComponentStorage].
-    #[allow(dead_code)]
     pub(super) fn new<T: Component>() -> Self {
-        Self::from_c_type(T::component_type())
-    }
-
-    /// Creates a new [ComponentStorage] from [ComponentType].
-    pub(super) fn from_c_type(c_type: ComponentType) -> Self {
         Self {
-            c_type,
-            ptrs: Vec::with_capacity(100_000),
+            c_type: T::component_type(),
+            name: std::any::type_name::<T>(),
+            size: std::mem::size_of::<T>(),
+            ptrs: Vec::new(),
         }
     }
 
+    /// Returns the type name of the components in this storage.
+    pub(super) fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the type of the components in this storage.
+    pub(super) fn component_type(&self) -> ComponentType {
+        self.c_type
+    }
+
+    /// Returns how many slots this storage has, including the empty ones.
+    pub(super) fn slots(&self) -> usize {
+        self.ptrs.len()
+    }
+
+    /// Returns how many components are stored.
+    pub(super) fn count(&self) -> usize {
+        self.ptrs.iter().filter(|ptr| ptr.is_some()).count()
+    }
+
+    /// Returns roughly how many bytes this storage uses, counting the reserved slots and the
+    /// boxed components.
+    pub(super) fn memory(&self) -> usize {
+        self.ptrs.capacity() * std::mem::size_of::<Option<ComponentPtr>>()
+            + self.count() * self.size
+    }
+
+    /// Reserves room for at least `additional` more components.
+    pub(super) fn reserve(&mut self, additional: usize) {
+        self.ptrs.reserve(additional);
+    }
+
     /// Adds a component into this storage and return it's index.
     ///
     /// Note: if the component you're trying to push is not the same type as this storage it won't
@@ -180,38 +338,74 @@ impl ComponentStorage {
     // TODO: write doc
     pub(super) unsafe fn push_unchecked<T: Component>(&mut self, component: T) -> usize {
         let component = ComponentPtr::new(component);
-        self.ptrs.push(component);
+        self.ptrs.push(Some(component));
+
+        self.ptrs.len() - 1
+    }
+
+    /// Replaces the component at the specified index, dropping the previous one.
+    ///
+    /// SAFETY: you must guarantee that the component is the same type as this storage and the
+    /// index is valid.
+    pub(super) unsafe fn replace_unchecked<T: Component>(&mut self, index: usize, component: T) {
+        *self.ptrs.get_unchecked_mut(index) = Some(ComponentPtr::new(component));
+    }
+
+    /// Takes every slot out of this storage, leaving it empty with room for the components that
+    /// were stored.
+    pub(super) fn take_slots(&mut self) -> Vec<Option<ComponentPtr>> {
+        let capacity = self.count();
+
+        std::mem::replace(&mut self.ptrs, Vec::with_capacity(capacity))
+    }
+
+    /// Adds a slot taken with [`ComponentStorage::take_slots`] back and returns its index.
+    pub(super) fn push_slot(&mut self, slot: Option<ComponentPtr>) -> usize {
+        self.ptrs.push(slot);
 
         self.ptrs.len() - 1
     }
 
+    /// Removes the component at the specified index and returns it.
+    ///
+    /// Note: the slot is left empty so the indexes of the other components stay valid.
+    pub(super) fn remove(&mut self, index: usize) -> Option<ComponentPtr> {
+        self.ptrs.get_mut(index).and_then(|ptr| ptr.take())
+    }
+
     /// Returns an iterator over the inner component in this storage.
     #[allow(dead_code)]
     pub(super) fn iter(&self) -> impl ParallelIterator<Item = &ComponentPtr> {
-        self.ptrs.par_iter()
+        self.ptrs.par_iter().flatten()
     }
 
     /// Returns an iterator over the inner component in this storage.
     #[allow(dead_code)]
     pub(super) fn iter_mut(&mut self) -> impl ParallelIterator<Item = &mut ComponentPtr> {
-        self.ptrs.par_iter_mut()
+        self.ptrs.par_iter_mut().flatten()
     }
 
     /// Gets a component reference from the specified index.
     #[allow(dead_code)]
     pub(super) fn get<T: Component>(&self, index: usize) -> Option<&T> {
-        self.ptrs.get(index).and_then(|ptr| ptr.cast_ref::<T>())
+        self.ptrs
+            .get(index)
+            .and_then(|ptr| ptr.as_ref())
+            .and_then(|ptr| ptr.cast_ref::<T>())
     }
 
     /// Gets a component reference from the specified index but unwraps.
     ///
     /// SAFETY: you must know that the index is valid before calling this method, this way you
     /// assure that the component exists.
+    #[allow(dead_code)]
     pub(super) unsafe fn get_unchecked<T: Component>(&self, index: usize) -> &T {
         unsafe {
             self.ptrs
                 .get(index)
                 .unwrap_unchecked()
+                .as_ref()
+                .unwrap_unchecked()
                 .cast_ref::<T>()
                 .unwrap_unchecked()
         }
@@ -221,11 +415,14 @@ impl ComponentStorage {
     ///
     /// SAFETY: you must know that the index is valid before calling this method, this way you
     /// assure that the component exists.
+    #[allow(dead_code)]
     pub(super) unsafe fn get_mut_unchecked<T: Component>(&mut self, index: usize) -> &mut T {
         unsafe {
             self.ptrs
                 .get_mut(index)
                 .unwrap_unchecked()
+                .as_mut()
+                .unwrap_unchecked()
                 .cast_mut::<T>()
                 .unwrap_unchecked()
         }
@@ -237,6 +434,13 @@ impl<T: Component> Bundle for T {
         vec![T::component_type()]
     }
 
+    fn register_components(storages: &mut Components, hooks: &mut ComponentHooks) {
+        if storages.storage(T::component_type()).is_none() {
+            storages.insert::<T>();
+            T::register_hooks(hooks);
+        }
+    }
+
     fn store_components(
         self,
         storages: &mut Components,
@@ -245,10 +449,20 @@ impl<T: Component> Bundle for T {
         // SAFETY: you must guarantee that the storage for this component already exists.
         let mut storage = unsafe { storages.storage_mut_unchecked(T::component_type()) };
 
-        // SAFETY: we now that the storage is the same type of this component in the get above.
-        let index = unsafe { storage.push_unchecked(self) };
+        let existing = component_indexes
+            .iter()
+            .find(|(c_type, _)| *c_type == T::component_type());
+
+        match existing {
+            // SAFETY: we now that the storage is the same type of this component in the get above.
+            Some(&(_, index)) => unsafe { storage.replace_unchecked(index, self) },
+            None => {
+                // SAFETY: same as above.
+                let index = unsafe { storage.push_unchecked(self) };
 
-        component_indexes.push((T::component_type(), index));
+                component_indexes.push((T::component_type(), index));
+            }
+        }
     }
 }
 
@@ -265,6 +479,12 @@ macro_rules! tuple_impl {
                     .collect()
             }
 
+            fn register_components(storages: &mut Components, hooks: &mut ComponentHooks) {
+                $(
+                    $name::register_components(storages, hooks);
+                )*
+            }
+
             fn store_components(
                 self,
                 storages: &mut Components,
@@ -1,31 +1,50 @@
+use std::fmt;
+
+use thiserror::Error;
+
 use super::archetype::ArchetypeId;
+use super::component::Component;
 use super::component::ComponentType;
+use super::name::Name;
+use super::query::ComponentMut;
+use super::query::ComponentRef;
+use super::world::World;
+
+/// Unique entity identifier, which is the index of the entity in our `entities`
+/// [`World`](crate::ecs::world::World) field.
+pub type EntityId = u128;
 
 /// Entity stored in our world.
-#[derive(Debug, Clone)]
+///
+/// Note: its `Debug` output shows the entity [`Name`] but only the indexes of its components, log
+/// the [`EntityRef`] returned by [`World::entity`] instead to see the components names.
+#[derive(Clone)]
 pub struct Entity {
     /// Unique identifier.
-    id: u128,
+    id: EntityId,
     /// Pointer to the [`Archetype`](crate::world::Archetype) stored in world, which is the key of the archetype in our
     /// `archetypes` [`World`](crate::world::World) field.
     archetype: ArchetypeId,
     /// Pointer to the components index stored in [`ComponentStorage`](crate::component::ComponentStorage).
     //c_ptrs: DashMap<ComponentType, usize, BuildHasherDefault<FxHasher>>,
     c_ptrs: Vec<(ComponentType, usize)>,
+    /// A copy of the entity [`Name`] for the `Debug` output, kept up to date by the world.
+    name: Option<Name>,
 }
 
 impl Entity {
     /// Creates a new [`Entity`] with its archetype and component ptrs indexex.
-    pub fn new(id: u128, archetype: ArchetypeId, c_ptrs: Vec<(ComponentType, usize)>) -> Self {
+    pub fn new(id: EntityId, archetype: ArchetypeId, c_ptrs: Vec<(ComponentType, usize)>) -> Self {
         Self {
             id,
             archetype,
             c_ptrs,
+            name: None,
         }
     }
 
     /// Returns the entity id.
-    pub fn id(&self) -> u128 {
+    pub fn id(&self) -> EntityId {
         self.id
     }
 
@@ -34,6 +53,43 @@ impl Entity {
         self.archetype
     }
 
+    /// Returns the types of the components this entity has.
+    pub fn components_types(&self) -> Vec<ComponentType> {
+        self.c_ptrs.iter().map(|(c_type, _)| *c_type).collect()
+    }
+
+    /// Returns the [`ComponentType`] and storage index of every component this entity has.
+    pub(super) fn c_ptrs(&self) -> &[(ComponentType, usize)] {
+        &self.c_ptrs
+    }
+
+    /// Returns a mutable reference to the [`ComponentType`] and storage index of every component
+    /// this entity has.
+    pub(super) fn c_ptrs_mut(&mut self) -> &mut Vec<(ComponentType, usize)> {
+        &mut self.c_ptrs
+    }
+
+    /// Sets the copy of the entity [`Name`] shown in the `Debug` output.
+    pub(super) fn set_name(&mut self, name: Option<Name>) {
+        self.name = name;
+    }
+
+    /// Moves this entity into another archetype.
+    pub(super) fn set_archetype_id(&mut self, archetype: ArchetypeId) {
+        self.archetype = archetype;
+    }
+
+    /// Gets the [`ComponentPtr`](crate::ecs::component::ComponentPtr) index in our
+    /// [`ComponentStorage`](crate::ecs::component) for the specified [`ComponentType`].
+    ///
+    /// Returns None if this entity doesn't have the component type.
+    pub(super) fn c_ptr(&self, c_type: ComponentType) -> Option<usize> {
+        self.c_ptrs
+            .iter()
+            .find(|(ct, _)| *ct == c_type)
+            .map(|(_, index)| *index)
+    }
+
     /// Gets the [`ComponentPtr`](crate::ecs::component::ComponentPtr) index in our
     /// [`ComponentStorage`](crate::ecs::component) for the specified [`ComponentType`].
     ///
@@ -46,3 +102,195 @@ impl Entity {
             .unwrap_unchecked()
     }
 }
+
+impl fmt::Debug for Entity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Entity");
+        debug.field("id", &self.id);
+        if let Some(name) = &self.name {
+            debug.field("name", &name.as_str());
+        }
+
+        debug
+            .field("archetype", &self.archetype)
+            .field("c_ptrs", &self.c_ptrs)
+            .finish()
+    }
+}
+
+/// A read only view of a single entity in the [`World`].
+///
+/// Example:
+///
+/// ```no_run
+/// use woody::ecs::world::World;
+/// use woody::ecs::component::Component;
+///
+/// #[derive(Debug, Component)]
+/// struct Health(u8);
+///
+/// let mut world = World::new();
+/// let player = world.spawn(Health(100));
+///
+/// let entity = world.entity(player).unwrap();
+///
+/// assert_eq!(entity.get::<Health>().unwrap().0, 100);
+/// println!("{:?}", entity.components_names());
+/// ```
+#[derive(Clone, Copy)]
+pub struct EntityRef<'w> {
+    world: &'w World,
+    entity: &'w Entity,
+}
+
+impl<'w> EntityRef<'w> {
+    pub(super) fn new(world: &'w World, entity: &'w Entity) -> Self {
+        Self { world, entity }
+    }
+
+    /// Returns the entity id.
+    pub fn id(&self) -> EntityId {
+        self.entity.id()
+    }
+
+    /// Returns the entity ArchetypeId.
+    pub fn archetype_id(&self) -> ArchetypeId {
+        self.entity.archetype_id()
+    }
+
+    /// Returns the types of the components this entity has.
+    pub fn components_types(&self) -> Vec<ComponentType> {
+        self.entity.components_types()
+    }
+
+    /// Returns the type names of the components this entity has.
+    pub fn components_names(&self) -> Vec<&'static str> {
+        self.entity
+            .c_ptrs()
+            .iter()
+            .filter_map(|(c_type, _)| self.world.components.name(*c_type))
+            .collect()
+    }
+
+    /// Returns the [`Name`] of this entity, if it has one.
+    pub fn name(&self) -> Option<&'w str> {
+        self.get::<Name>()
+            .ok()
+            .map(|name| name.into_inner().as_str())
+    }
+
+    /// Checks if this entity has a component of type `T`.
+    pub fn has<T: Component>(&self) -> bool {
+        self.entity.c_ptr(T::component_type()).is_some()
+    }
+
+    /// Gets a reference to the component `T` of this entity.
+    pub fn get<T: Component>(&self) -> Result<ComponentRef<'w, T>, EntityError> {
+        self.world.get::<T>(self.id())
+    }
+}
+
+impl fmt::Debug for EntityRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("EntityRef");
+        debug.field("id", &self.id());
+        if let Some(name) = self.name() {
+            debug.field("name", &name);
+        }
+
+        debug
+            .field("archetype", &self.archetype_id())
+            .field("components", &self.components_names())
+            .finish()
+    }
+}
+
+/// A mutable view of a single entity in the [`World`].
+pub struct EntityMut<'w> {
+    world: &'w mut World,
+    id: EntityId,
+}
+
+impl<'w> EntityMut<'w> {
+    pub(super) fn new(world: &'w mut World, id: EntityId) -> Self {
+        Self { world, id }
+    }
+
+    /// Returns the entity id.
+    pub fn id(&self) -> EntityId {
+        self.id
+    }
+
+    /// Returns a read only view of this entity.
+    pub fn as_readonly(&self) -> EntityRef<'_> {
+        // SAFETY: an EntityMut is only created for entities that are alive.
+        let entity = unsafe { self.world.entity_unchecked(self.id) };
+
+        EntityRef::new(self.world, entity)
+    }
+
+    /// Returns the entity ArchetypeId.
+    pub fn archetype_id(&self) -> ArchetypeId {
+        self.as_readonly().archetype_id()
+    }
+
+    /// Returns the [`Name`] of this entity, if it has one.
+    pub fn name(&self) -> Option<&str> {
+        self.as_readonly().name()
+    }
+
+    /// Returns the types of the components this entity has.
+    pub fn components_types(&self) -> Vec<ComponentType> {
+        self.as_readonly().components_types()
+    }
+
+    /// Returns the type names of the components this entity has.
+    pub fn components_names(&self) -> Vec<&'static str> {
+        self.as_readonly().components_names()
+    }
+
+    /// Checks if this entity has a component of type `T`.
+    pub fn has<T: Component>(&self) -> bool {
+        self.as_readonly().has::<T>()
+    }
+
+    /// Gets a reference to the component `T` of this entity.
+    pub fn get<T: Component>(&self) -> Result<ComponentRef<'_, T>, EntityError> {
+        self.world.get::<T>(self.id)
+    }
+
+    /// Gets a mutable reference to the component `T` of this entity.
+    pub fn get_mut<T: Component>(&mut self) -> Result<ComponentMut<'_, T>, EntityError> {
+        self.world.get_mut::<T>(self.id)
+    }
+}
+
+impl fmt::Debug for EntityMut<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("EntityMut");
+        debug.field("id", &self.id);
+        if let Some(name) = self.name() {
+            debug.field("name", &name);
+        }
+
+        debug
+            .field("archetype", &self.archetype_id())
+            .field("components", &self.components_names())
+            .finish()
+    }
+}
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum EntityError {
+    /// The entity was never spawned in this world.
+    #[error("Entity {0} does not exist")]
+    NotFound(EntityId),
+
+    /// The entity existed but has been despawned.
+    #[error("Entity {0} was despawned")]
+    Despawned(EntityId),
+
+    /// The entity is alive but doesn't have the requested component.
+    #[error("Entity {0} does not have component {1}")]
+    MissingComponent(EntityId, &'static str),
+}
@@ -0,0 +1,148 @@
+use rustc_hash::FxHashMap;
+
+use super::command::Commands;
+use super::component::Component;
+use super::component::ComponentType;
+use super::entity::EntityId;
+use super::world::World;
+
+/// A function invoked when a component of an entity is added, inserted or removed.
+///
+/// The world can't be mutated while the hook runs, so it receives a [`Commands`] queue that is
+/// applied right after the operation that triggered the hook finishes.
+pub type ComponentHook = fn(&World, EntityId, &mut Commands);
+
+/// When a [`ComponentHook`] is invoked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookKind {
+    /// The component was added to an entity that didn't have it, by spawning or inserting.
+    ///
+    /// Runs after the component is stored.
+    Add,
+    /// The component was stored in an entity, either added or replacing the previous value.
+    ///
+    /// Runs after the component is stored and after [`HookKind::Add`].
+    Insert,
+    /// The component is about to be removed from an entity, by removing, replacing or
+    /// despawning.
+    ///
+    /// Runs before the component is dropped, so it can still be read.
+    Remove,
+}
+
+/// The hooks registered for a single component type.
+#[derive(Debug, Default, Clone)]
+struct Hooks {
+    on_add: Vec<ComponentHook>,
+    on_insert: Vec<ComponentHook>,
+    on_remove: Vec<ComponentHook>,
+}
+
+impl Hooks {
+    fn get(&self, kind: HookKind) -> &[ComponentHook] {
+        match kind {
+            HookKind::Add => &self.on_add,
+            HookKind::Insert => &self.on_insert,
+            HookKind::Remove => &self.on_remove,
+        }
+    }
+
+    fn get_mut(&mut self, kind: HookKind) -> &mut Vec<ComponentHook> {
+        match kind {
+            HookKind::Add => &mut self.on_add,
+            HookKind::Insert => &mut self.on_insert,
+            HookKind::Remove => &mut self.on_remove,
+        }
+    }
+}
+
+/// A storage of component hooks.
+#[derive(Debug, Default)]
+pub struct ComponentHooks(FxHashMap<ComponentType, Hooks>);
+
+impl ComponentHooks {
+    /// Registers a hook for the component `T`.
+    pub fn add<T: Component>(&mut self, kind: HookKind, hook: ComponentHook) {
+        self.0
+            .entry(T::component_type())
+            .or_default()
+            .get_mut(kind)
+            .push(hook);
+    }
+
+    /// Invokes the hooks of each component type for an entity, in the order they were
+    /// registered.
+    pub(super) fn trigger(
+        &self,
+        kind: HookKind,
+        c_types: &[ComponentType],
+        world: &World,
+        entity: EntityId,
+        commands: &mut Commands,
+    ) {
+        if self.0.is_empty() {
+            return;
+        }
+
+        for c_type in c_types {
+            if let Some(hooks) = self.0.get(c_type) {
+                for hook in hooks.get(kind) {
+                    hook(world, entity, commands);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+
+    #[derive(Debug)]
+    struct Position(u8);
+    impl Component for Position {}
+
+    #[derive(Debug)]
+    struct Velocity(u8);
+    impl Component for Velocity {}
+
+    #[test]
+    fn hooks() {
+        static ADDED: AtomicUsize = AtomicUsize::new(0);
+        static INSERTED: AtomicUsize = AtomicUsize::new(0);
+        static REMOVED: AtomicUsize = AtomicUsize::new(0);
+
+        fn on_add(_: &World, entity: EntityId, commands: &mut Commands) {
+            ADDED.fetch_add(1, Ordering::SeqCst);
+            commands.insert(entity, Velocity(7));
+        }
+
+        fn on_insert(_: &World, _: EntityId, _: &mut Commands) {
+            INSERTED.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn on_remove(world: &World, entity: EntityId, _: &mut Commands) {
+            // The component can still be read while being removed.
+            assert!(world.get::<Position>(entity).is_ok());
+            REMOVED.fetch_add(1, Ordering::SeqCst);
+        }
+
+        let mut world = World::new();
+        world.on_add::<Position>(on_add);
+        world.on_insert::<Position>(on_insert);
+        world.on_remove::<Position>(on_remove);
+
+        let entity = world.spawn(Position(0));
+        assert_eq!(world.get::<Velocity>(entity).unwrap().0, 7);
+
+        world.insert(entity, Position(1)).unwrap();
+        world.despawn(entity).unwrap();
+
+        assert_eq!(ADDED.load(Ordering::SeqCst), 1);
+        assert_eq!(INSERTED.load(Ordering::SeqCst), 2);
+        assert_eq!(REMOVED.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,225 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::hash::BuildHasherDefault;
+
+use dashmap::DashMap;
+use log::warn;
+use rustc_hash::FxHasher;
+use smallvec::SmallVec;
+
+use super::command::Commands;
+use super::component::Component;
+use super::component::ComponentType;
+use super::entity::EntityId;
+use super::hook::ComponentHooks;
+use super::hook::HookKind;
+use super::world::World;
+
+/// A human readable name of an entity, shown in the `Debug` output of its
+/// [`Entity`](super::entity::Entity) and [`EntityRef`](super::entity::EntityRef) and indexed by the
+/// world so the entity can be found with [`World::find_by_name`].
+///
+/// Names don't have to be unique, but a warning is logged whenever one is given to more than one
+/// entity, see [`World::duplicate_names`].
+///
+/// Note: replace a name by inserting a new one, assigning it through
+/// [`World::get_mut`](super::world::World::get_mut) goes around the index and the `Debug` output.
+///
+/// Example:
+/// ```no_run
+/// use woody::ecs::name::Name;
+/// use woody::ecs::world::World;
+///
+/// let mut world = World::new();
+/// let player = world.spawn(Name::new("player"));
+///
+/// assert_eq!(world.find_by_name("player"), Some(player));
+/// println!("{:?}", world.entity(player).unwrap());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Name(Cow<'static, str>);
+
+impl Name {
+    /// Creates a new [`Name`].
+    pub fn new(name: impl Into<Cow<'static, str>>) -> Self {
+        Self(name.into())
+    }
+
+    /// Returns the name as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Component for Name {
+    fn register_hooks(hooks: &mut ComponentHooks) {
+        hooks.add::<Self>(HookKind::Insert, index);
+        hooks.add::<Self>(HookKind::Remove, unindex);
+    }
+}
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for Name {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&'static str> for Name {
+    fn from(name: &'static str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for Name {
+    fn from(name: String) -> Self {
+        Self::new(name)
+    }
+}
+
+/// Indexes the [`Name`] of an entity once it's stored.
+fn index(world: &World, entity: EntityId, _: &mut Commands) {
+    let Ok(name) = world.get::<Name>(entity) else {
+        return;
+    };
+
+    let mut entities = world.names.0.entry(name.as_str().to_owned()).or_default();
+    if !entities.contains(&entity) {
+        entities.push(entity);
+    }
+
+    if entities.len() > 1 {
+        warn!(
+            "Entity {} is named `{}` like the entities {:?}",
+            entity,
+            name.as_str(),
+            entities
+                .iter()
+                .filter(|&&e| e != entity)
+                .collect::<Vec<_>>()
+        );
+    }
+}
+
+/// Removes the [`Name`] of an entity from the index before it's removed.
+fn unindex(world: &World, entity: EntityId, _: &mut Commands) {
+    let Ok(name) = world.get::<Name>(entity) else {
+        return;
+    };
+
+    world.names.0.remove_if_mut(name.as_str(), |_, entities| {
+        entities.retain(|e| *e != entity);
+        entities.is_empty()
+    });
+}
+
+/// The index of [`Name`]s, mapping each name to the entities that have it.
+///
+/// Note: it's kept up to date by the hooks of [`Name`], which only get a shared reference to the
+/// world.
+#[derive(Debug, Default)]
+pub(super) struct NameIndex(DashMap<String, SmallVec<[EntityId; 1]>, BuildHasherDefault<FxHasher>>);
+
+impl World {
+    /// Copies the [`Name`] of an entity into it for its `Debug` output, if `c_types` were just
+    /// stored or removed and include it.
+    pub(super) fn cache_name(&mut self, entity: EntityId, c_types: &[ComponentType]) {
+        if !c_types.contains(&Name::component_type()) {
+            return;
+        }
+
+        let name = self.get::<Name>(entity).ok().map(|name| Name::clone(&name));
+        if let Some(stored) = self
+            .entities
+            .get_mut(entity as usize)
+            .and_then(Option::as_mut)
+        {
+            stored.set_name(name);
+        }
+    }
+
+    /// Finds the entity with the [`Name`] `name`.
+    ///
+    /// Note: if more than one entity has that name, the one named first is returned.
+    pub fn find_by_name(&self, name: &str) -> Option<EntityId> {
+        self.names
+            .0
+            .get(name)
+            .and_then(|entities| entities.first().copied())
+    }
+
+    /// Finds all entities with the [`Name`] `name`, in the order they were named.
+    pub fn find_all_by_name(&self, name: &str) -> Vec<EntityId> {
+        self.names
+            .0
+            .get(name)
+            .map(|entities| entities.to_vec())
+            .unwrap_or_default()
+    }
+
+    /// Returns every name given to more than one entity along with those entities, sorted by
+    /// name.
+    pub fn duplicate_names(&self) -> Vec<(String, Vec<EntityId>)> {
+        let mut duplicates: Vec<_> = self
+            .names
+            .0
+            .iter()
+            .filter(|entry| entry.value().len() > 1)
+            .map(|entry| (entry.key().clone(), entry.value().to_vec()))
+            .collect();
+        duplicates.sort();
+
+        duplicates
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Position(u8);
+    impl Component for Position {}
+
+    #[test]
+    fn names() {
+        let mut world = World::new();
+
+        let player = world.spawn((Name::new("player"), Position(0)));
+        let enemy = world.spawn(Name::new("enemy"));
+        let other = world.spawn(Name::from(String::from("enemy")));
+
+        assert_eq!(world.find_by_name("player"), Some(player));
+        assert_eq!(world.find_by_name("enemy"), Some(enemy));
+        assert_eq!(world.find_all_by_name("enemy"), vec![enemy, other]);
+        assert_eq!(world.find_by_name("boss"), None);
+        assert_eq!(
+            world.duplicate_names(),
+            vec![(String::from("enemy"), vec![enemy, other])]
+        );
+
+        let debug = format!("{:?}", world.entity(player).unwrap());
+        assert!(debug.contains("name: \"player\""));
+        let debug = format!("{:?}", world.entity_checked(player).unwrap());
+        assert!(debug.contains("name: \"player\""));
+
+        world.insert(other, Name::new("boss")).unwrap();
+        world.despawn(enemy).unwrap();
+        assert_eq!(world.find_by_name("boss"), Some(other));
+        assert_eq!(world.find_by_name("enemy"), None);
+        assert!(world.duplicate_names().is_empty());
+        let debug = format!("{:?}", world.entity_checked(other).unwrap());
+        assert!(debug.contains("name: \"boss\""));
+
+        world.remove::<Name>(player).unwrap();
+        assert_eq!(world.find_by_name("player"), None);
+        assert_eq!(world.entity(player).unwrap().name(), None);
+        let debug = format!("{:?}", world.entity_checked(player).unwrap());
+        assert!(!debug.contains("name"));
+    }
+}
@@ -1,132 +1,261 @@
+use std::any::type_name;
+use std::fmt;
 use std::marker::PhantomData;
 use std::ops::Deref;
 use std::ops::DerefMut;
 
 use rayon::prelude::IntoParallelRefIterator;
 use rayon::prelude::ParallelIterator;
+use thiserror::Error;
 
+use super::archetype::ArchetypeId;
+use super::component::Column;
+use super::component::ColumnMut;
 use super::component::Component;
-use super::component::ComponentStorage;
 use super::component::ComponentType;
 use super::component::Components;
 use super::entity::Entity;
+use super::entity::EntityId;
 use super::world::World;
-use super::FxMappedRef;
-use super::FxMappedRefMut;
-use super::FxRef;
-use super::FxRefMut;
-use std::hash::Hash;
 
 #[repr(transparent)]
-#[derive(Debug)]
-pub struct MappedRefWrapper<'a, K: Hash + Eq, V, T>(FxMappedRef<'a, K, V, T>);
+pub struct MappedRefWrapper<'a, T>(&'a T);
 
-unsafe impl<'a, K: Hash + Eq, V, T> Send for MappedRefWrapper<'a, K, V, T> {}
-unsafe impl<'a, K: Hash + Eq, V, T> Sync for MappedRefWrapper<'a, K, V, T> {}
+impl<'a, T> MappedRefWrapper<'a, T> {
+    /// Returns a reference to the value.
+    pub fn value(&self) -> &T {
+        self.0
+    }
 
-impl<'a, K: Hash + Eq, V, T> Deref for MappedRefWrapper<'a, K, V, T> {
-    type Target = FxMappedRef<'a, K, V, T>;
+    /// Returns the inner reference, which outlives the wrapper.
+    pub fn into_inner(self) -> &'a T {
+        self.0
+    }
+}
+
+impl<'a, T> Deref for MappedRefWrapper<'a, T> {
+    type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        self.0
     }
 }
 
-impl<'a, K: Hash + Eq, V, T> DerefMut for MappedRefWrapper<'a, K, V, T> {
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+impl<'a, T: fmt::Debug> fmt::Debug for MappedRefWrapper<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
     }
 }
 
 #[repr(transparent)]
-#[derive(Debug)]
-pub struct MappedRefMutWrapper<'a, K: Hash + Eq, V, T>(FxMappedRefMut<'a, K, V, T>);
+pub struct MappedRefMutWrapper<'a, T>(&'a mut T);
 
-unsafe impl<'a, K: Hash + Eq, V, T> Send for MappedRefMutWrapper<'a, K, V, T> {}
-unsafe impl<'a, K: Hash + Eq, V, T> Sync for MappedRefMutWrapper<'a, K, V, T> {}
+impl<'a, T> MappedRefMutWrapper<'a, T> {
+    /// Returns a reference to the value.
+    pub fn value(&self) -> &T {
+        self.0
+    }
 
-impl<'a, K: Hash + Eq, V, T> Deref for MappedRefMutWrapper<'a, K, V, T> {
-    type Target = FxMappedRefMut<'a, K, V, T>;
+    /// Returns a mutable reference to the value.
+    pub fn value_mut(&mut self) -> &mut T {
+        self.0
+    }
+}
+
+impl<'a, T> Deref for MappedRefMutWrapper<'a, T> {
+    type Target = T;
 
     fn deref(&self) -> &Self::Target {
-        &self.0
+        self.0
     }
 }
 
-impl<'a, K: Hash + Eq, V, T> DerefMut for MappedRefMutWrapper<'a, K, V, T> {
+impl<'a, T> DerefMut for MappedRefMutWrapper<'a, T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        self.0
+    }
+}
+
+impl<'a, T: fmt::Debug> fmt::Debug for MappedRefMutWrapper<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A reference to a single component of type `T`.
+pub type ComponentRef<'a, T> = MappedRefWrapper<'a, T>;
+
+/// A mutable reference to a single component of type `T`.
+pub type ComponentMut<'a, T> = MappedRefMutWrapper<'a, T>;
+
+/// How a query borrows the column of a component type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnBorrow {
+    pub c_type: ComponentType,
+    /// The component type name, used in conflict messages.
+    pub name: &'static str,
+    pub mutable: bool,
+}
+
+impl ColumnBorrow {
+    /// Creates a read only borrow of the column of `T`.
+    pub fn read<T: Component>() -> Self {
+        Self {
+            c_type: T::component_type(),
+            name: type_name::<T>(),
+            mutable: false,
+        }
+    }
+
+    /// Creates a mutable borrow of the column of `T`.
+    pub fn write<T: Component>() -> Self {
+        Self {
+            mutable: true,
+            ..Self::read::<T>()
+        }
+    }
+
+    /// Checks if both borrows can't be held at the same time.
+    pub fn conflicts_with(&self, other: &ColumnBorrow) -> bool {
+        self.c_type == other.c_type && (self.mutable || other.mutable)
+    }
+}
+
+/// Tracks the column borrows of a query.
+///
+/// A column may be borrowed many times read only or once mutably, just like a `RefCell`, which is
+/// what makes handing out the items of a query (even in parallel) sound without locking the
+/// storages for each item.
+#[derive(Debug, Default, Clone)]
+pub struct ColumnBorrows(Vec<ColumnBorrow>);
+
+impl ColumnBorrows {
+    /// Registers a borrow, returning the one it conflicts with if there's any.
+    pub fn borrow(&mut self, borrow: ColumnBorrow) -> Result<(), ColumnBorrow> {
+        if let Some(conflict) = self.0.iter().find(|other| other.conflicts_with(&borrow)) {
+            return Err(*conflict);
+        }
+
+        self.0.push(borrow);
+
+        Ok(())
+    }
+
+    /// Returns an iterator over the registered borrows.
+    pub fn iter(&self) -> impl Iterator<Item = &ColumnBorrow> {
+        self.0.iter()
     }
 }
 
 pub trait Query {
     type Item<'a>: Send + Sync;
-    type Storage<'a>;
+    type Storage<'a>: Copy + Send + Sync;
     type ComponentIndex;
 
     fn components_types() -> Vec<ComponentType>;
 
-    fn init_storage(storages: &Components) -> Self::Storage<'_>;
+    /// Registers how this query borrows the component columns.
+    fn borrow_columns(borrows: &mut ColumnBorrows) -> Result<(), ColumnBorrow>;
+
+    /// Gets the storages of this query without locking them.
+    ///
+    /// # Safety
+    ///
+    /// Every storage must exist, and you must guarantee that nothing else accesses them
+    /// in a way that conflicts with [`Query::borrow_columns`] for as long as the storage lives.
+    unsafe fn init_storage(storages: &Components) -> Self::Storage<'_>;
 
     fn get_component_index(entity: Entity) -> Self::ComponentIndex;
 
-    fn fetch(storage: Self::Storage<'_>, c_ptr: Self::ComponentIndex) -> Self::Item<'_>;
+    /// Fetches the item from the storages.
+    ///
+    /// # Safety
+    ///
+    /// The component index must be valid and it must not be fetched again from the same
+    /// storage while the returned item is alive.
+    unsafe fn fetch(storage: Self::Storage<'_>, c_ptr: Self::ComponentIndex) -> Self::Item<'_>;
 }
 
 impl<T: Component> Query for &T {
-    type Item<'a> = MappedRefWrapper<'a, ComponentType, ComponentStorage, T>;
-    type Storage<'a> = FxRef<'a, ComponentType, ComponentStorage>;
+    type Item<'a> = ComponentRef<'a, T>;
+    type Storage<'a> = Column<'a>;
     type ComponentIndex = usize;
 
     fn components_types() -> Vec<ComponentType> {
         vec![T::component_type()]
     }
 
-    fn init_storage(storages: &Components) -> Self::Storage<'_> {
-        // SAFETY: there's none.
-        unsafe { storages.storage_unchecked(T::component_type()) }
+    fn borrow_columns(borrows: &mut ColumnBorrows) -> Result<(), ColumnBorrow> {
+        borrows.borrow(ColumnBorrow::read::<T>())
+    }
+
+    unsafe fn init_storage(storages: &Components) -> Self::Storage<'_> {
+        storages.column_unchecked(T::component_type())
     }
 
     fn get_component_index(entity: Entity) -> Self::ComponentIndex {
         unsafe { entity.c_ptr_unchecked(T::component_type()) }
     }
 
-    fn fetch(storage: Self::Storage<'_>, c_ptr: Self::ComponentIndex) -> Self::Item<'_> {
-        MappedRefWrapper(storage.map(|storage| unsafe { storage.get_unchecked::<T>(c_ptr) }))
+    unsafe fn fetch(storage: Self::Storage<'_>, c_ptr: Self::ComponentIndex) -> Self::Item<'_> {
+        MappedRefWrapper(storage.get_unchecked::<T>(c_ptr))
     }
 }
 
 impl<T: Component> Query for &mut T {
-    type Item<'a> = MappedRefMutWrapper<'a, ComponentType, ComponentStorage, T>;
-    type Storage<'a> = FxRefMut<'a, ComponentType, ComponentStorage>;
+    type Item<'a> = ComponentMut<'a, T>;
+    type Storage<'a> = ColumnMut<'a>;
     type ComponentIndex = usize;
 
     fn components_types() -> Vec<ComponentType> {
         vec![T::component_type()]
     }
 
-    fn init_storage(storages: &Components) -> Self::Storage<'_> {
-        // SAFETY: there's none.
-        unsafe { storages.storage_mut_unchecked(T::component_type()) }
+    fn borrow_columns(borrows: &mut ColumnBorrows) -> Result<(), ColumnBorrow> {
+        borrows.borrow(ColumnBorrow::write::<T>())
+    }
+
+    unsafe fn init_storage(storages: &Components) -> Self::Storage<'_> {
+        storages.column_mut_unchecked(T::component_type())
     }
 
     fn get_component_index(entity: Entity) -> Self::ComponentIndex {
         unsafe { entity.c_ptr_unchecked(T::component_type()) }
     }
 
-    fn fetch(storage: Self::Storage<'_>, c_ptr: Self::ComponentIndex) -> Self::Item<'_> {
-        MappedRefMutWrapper(storage.map(|storage| unsafe { storage.get_mut_unchecked::<T>(c_ptr) }))
+    unsafe fn fetch(storage: Self::Storage<'_>, c_ptr: Self::ComponentIndex) -> Self::Item<'_> {
+        MappedRefMutWrapper(storage.get_unchecked::<T>(c_ptr))
     }
 }
 
 pub struct QueryState<'a, Q: Query> {
     entities: Vec<&'a Entity>,
+    /// Every entity of the world, indexed by id, to look entities up without iterating.
+    table: &'a [Option<Entity>],
+    /// The archetypes matched by this query.
+    archetypes: Vec<ArchetypeId>,
     storages: &'a mut Components,
+    borrows: ColumnBorrows,
     marker: PhantomData<Q>,
 }
 
 impl<'a, Q: Query> QueryState<'a, Q> {
+    /// Creates a new [`QueryState`].
+    ///
+    /// Panics if the query borrows a component mutably more than once, or both mutably and read
+    /// only, e.g. `(&mut Position, &Position)`.
     pub fn new(world: &'a mut World) -> Self {
+        let mut borrows = ColumnBorrows::default();
+
+        if let Err(conflict) = Q::borrow_columns(&mut borrows) {
+            panic!(
+                "Query `{}` borrows component `{}` mutably while also borrowing it elsewhere in \
+                 the same query",
+                type_name::<Q>(),
+                conflict.name
+            );
+        }
+
         let c_types = Q::components_types();
 
         let archetypes: Vec<_> = world
@@ -141,32 +270,150 @@ impl<'a, Q: Query> QueryState<'a, Q> {
                 archetype
                     .entities()
                     .par_iter()
-                    .map(|entity| &world.entities[*entity])
+                    .filter_map(|entity| world.entities[*entity].as_ref())
             })
             .collect();
 
+        let archetypes = archetypes
+            .iter()
+            .map(|archetype| *archetype.key())
+            .collect();
+
         Self {
             storages: &mut world.components,
+            table: &world.entities,
+            archetypes,
             entities,
+            borrows,
             marker: PhantomData,
         }
     }
 
+    /// Returns how this query borrows the component columns.
+    pub fn borrows(&self) -> &ColumnBorrows {
+        &self.borrows
+    }
+
+    /// Returns how many entities this query matched.
+    pub fn len(&self) -> usize {
+        self.entities.len()
+    }
+
+    /// Checks if this query didn't match any entity.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
     pub fn par_iter(&mut self) -> impl ParallelIterator<Item = Q::Item<'_>> {
-        self.entities.par_iter().map(|&entity| {
-            let storage = Q::init_storage(self.storages);
+        let storage = self.storage();
+
+        self.entities.par_iter().map(move |&entity| {
+            // SAFETY: the storage is only None when there's no entity to iterate over.
+            let storage = unsafe { storage.unwrap_unchecked() };
             let c_ptr = Q::get_component_index(entity.clone());
-            Q::fetch(storage, c_ptr)
+
+            // SAFETY: each entity is fetched only once.
+            unsafe { Q::fetch(storage, c_ptr) }
         })
     }
 
     pub fn iter(&mut self) -> impl Iterator<Item = Q::Item<'_>> {
-        self.entities.iter().map(|&entity| {
-            let storage = Q::init_storage(self.storages);
+        let storage = self.storage();
+
+        self.entities.iter().map(move |&entity| {
+            // SAFETY: the storage is only None when there's no entity to iterate over.
+            let storage = unsafe { storage.unwrap_unchecked() };
             let c_ptr = Q::get_component_index(entity.clone());
-            Q::fetch(storage, c_ptr)
+
+            // SAFETY: each entity is fetched only once.
+            unsafe { Q::fetch(storage, c_ptr) }
         })
     }
+
+    /// Fetches the items of many entities at once, e.g. `&mut Health` of both the attacker and the
+    /// target of a collision.
+    ///
+    /// Returns [`QueryError::Aliasing`] if the same entity is requested more than once.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// use woody::ecs::world::World;
+    /// use woody::ecs::component::Component;
+    ///
+    /// #[derive(Debug, Component)]
+    /// struct Health(u8);
+    ///
+    /// let mut world = World::new();
+    ///
+    /// let attacker = world.spawn(Health(100));
+    /// let target = world.spawn(Health(100));
+    ///
+    /// let mut query = world.query::<&mut Health>();
+    /// let [mut attacker, mut target] = query.get_many_mut([attacker, target]).unwrap();
+    ///
+    /// attacker.0 -= 10;
+    /// target.0 -= 20;
+    /// ```
+    pub fn get_many_mut<const N: usize>(
+        &mut self,
+        entities: [EntityId; N],
+    ) -> Result<[Q::Item<'_>; N], QueryError> {
+        let mut matched: [Option<&Entity>; N] = [None; N];
+
+        for (index, (slot, id)) in matched.iter_mut().zip(entities).enumerate() {
+            if entities[..index].contains(&id) {
+                return Err(QueryError::Aliasing(id));
+            }
+
+            let entity = usize::try_from(id)
+                .ok()
+                .and_then(|index| self.table.get(index))
+                .and_then(Option::as_ref)
+                .filter(|entity| self.archetypes.contains(&entity.archetype_id()))
+                .ok_or(QueryError::NoMatch(id))?;
+
+            *slot = Some(entity);
+        }
+
+        let storage = self.storage();
+
+        Ok(matched.map(|entity| {
+            // SAFETY: every slot was filled in the loop above, which also means the storage isn't
+            // None.
+            let (entity, storage) =
+                unsafe { (entity.unwrap_unchecked(), storage.unwrap_unchecked()) };
+            let c_ptr = Q::get_component_index(entity.clone());
+
+            // SAFETY: we've checked above that every entity is different.
+            unsafe { Q::fetch(storage, c_ptr) }
+        }))
+    }
+
+    /// Gets the query storages, which is None when no entity matches the query since some of the
+    /// storages may not exist.
+    fn storage(&self) -> Option<Q::Storage<'_>> {
+        if self.entities.is_empty() {
+            return None;
+        }
+
+        // SAFETY: every matched entity has all the components of the query, so their storages
+        // exist. We're holding the world mutably so nothing else can access the storages, and
+        // the borrows of the query itself were checked in `new`.
+        Some(unsafe { Q::init_storage(self.storages) })
+    }
+}
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum QueryError {
+    /// The entity doesn't exist or doesn't have the components of the query.
+    #[error("Entity {0} does not match the query")]
+    NoMatch(EntityId),
+
+    /// The same entity was requested more than once, which would alias mutable references.
+    #[error("Entity {0} was requested more than once")]
+    Aliasing(EntityId),
 }
 
 macro_rules! tuple_impl {
@@ -186,7 +433,15 @@ macro_rules! tuple_impl {
                     .collect()
             }
 
-            fn init_storage(storages: &Components) -> Self::Storage<'_> {
+            fn borrow_columns(borrows: &mut ColumnBorrows) -> Result<(), ColumnBorrow> {
+                $(
+                    $name::borrow_columns(borrows)?;
+                )*
+
+                Ok(())
+            }
+
+            unsafe fn init_storage(storages: &Components) -> Self::Storage<'_> {
                 ( $( $name::init_storage(storages), )* )
             }
 
@@ -194,7 +449,7 @@ macro_rules! tuple_impl {
                 ( $( $name::get_component_index(entity.clone()), )* )
             }
 
-            fn fetch(storage: Self::Storage<'_>, c_ptr: Self::ComponentIndex) -> Self::Item<'_> {
+            unsafe fn fetch(storage: Self::Storage<'_>, c_ptr: Self::ComponentIndex) -> Self::Item<'_> {
                 let ($($name,)*) = storage;
                 let ($(paste::paste! { [<$name ptr>] },)*) = c_ptr;
 
@@ -0,0 +1,452 @@
+use std::any::type_name;
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use std::hash::BuildHasherDefault;
+use std::marker::PhantomData;
+
+use dashmap::DashMap;
+use log::warn;
+use rustc_hash::FxHashSet;
+use rustc_hash::FxHasher;
+use smallvec::SmallVec;
+
+use super::command::Commands;
+use super::component::Component;
+use super::component::ComponentType;
+use super::entity::EntityError;
+use super::entity::EntityId;
+use super::hook::ComponentHooks;
+use super::hook::HookKind;
+use super::world::World;
+
+/// A kind of relationship between entities, used to tell [`Relation`]s apart.
+///
+/// Example:
+/// ```no_run
+/// use woody::ecs::component::Component;
+/// use woody::ecs::relation::Relation;
+/// use woody::ecs::relation::RelationKind;
+/// use woody::ecs::world::World;
+///
+/// #[derive(Component)]
+/// struct Transform;
+///
+/// struct ChildOf;
+/// impl RelationKind for ChildOf {}
+///
+/// let mut world = World::new();
+/// let parent = world.spawn(Transform);
+/// let child = world.spawn(Relation::<ChildOf>::new(parent));
+///
+/// assert_eq!(world.sources::<ChildOf>(parent), vec![child]);
+/// ```
+pub trait RelationKind: Send + Sync + 'static {}
+
+/// A component relating an entity to one or more target entities through the relationship `R`.
+///
+/// The world keeps a reverse index of every relation, so the entities pointing at a target can be
+/// found with [`World::sources`], and when a target is despawned it's removed from the relations
+/// pointing at it, removing the relation entirely when no targets are left. The same goes for the
+/// targets which don't exist when the relation is stored, along with a warning.
+///
+/// Note: the targets can't be changed in place since that would go around the reverse index,
+/// insert a new relation or use [`World::relate`] and [`World::unrelate`] instead.
+pub struct Relation<R: RelationKind> {
+    targets: SmallVec<[EntityId; 1]>,
+    marker: PhantomData<R>,
+}
+
+impl<R: RelationKind> Relation<R> {
+    /// Creates a relation to a single target.
+    pub fn new(target: EntityId) -> Self {
+        Self::many([target])
+    }
+
+    /// Creates a relation to many targets, duplicates are ignored.
+    pub fn many(targets: impl IntoIterator<Item = EntityId>) -> Self {
+        let mut relation = Self {
+            targets: SmallVec::new(),
+            marker: PhantomData,
+        };
+
+        for target in targets {
+            if !relation.targets.contains(&target) {
+                relation.targets.push(target);
+            }
+        }
+
+        relation
+    }
+
+    /// Returns the first target of this relation.
+    pub fn target(&self) -> Option<EntityId> {
+        self.targets.first().copied()
+    }
+
+    /// Returns all targets of this relation.
+    pub fn targets(&self) -> &[EntityId] {
+        &self.targets
+    }
+
+    /// Checks if `target` is one of the targets of this relation.
+    pub fn contains(&self, target: EntityId) -> bool {
+        self.targets.contains(&target)
+    }
+}
+
+impl<R: RelationKind> Component for Relation<R> {
+    fn register_hooks(hooks: &mut ComponentHooks) {
+        hooks.add::<Self>(HookKind::Insert, link::<R>);
+        hooks.add::<Self>(HookKind::Remove, unlink::<R>);
+    }
+}
+
+impl<R: RelationKind> Clone for Relation<R> {
+    fn clone(&self) -> Self {
+        Self {
+            targets: self.targets.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<R: RelationKind> PartialEq for Relation<R> {
+    fn eq(&self, other: &Self) -> bool {
+        self.targets == other.targets
+    }
+}
+
+impl<R: RelationKind> Eq for Relation<R> {}
+
+impl<R: RelationKind> Debug for Relation<R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Relation")
+            .field("kind", &std::any::type_name::<R>())
+            .field("targets", &self.targets)
+            .finish()
+    }
+}
+
+/// Indexes the targets of a [`Relation`] once it's stored in an entity.
+///
+/// Note: the targets which don't exist are dropped from the relation instead.
+fn link<R: RelationKind>(world: &World, entity: EntityId, commands: &mut Commands) {
+    let Ok(relation) = world.get::<Relation<R>>(entity) else {
+        return;
+    };
+
+    for &target in relation.targets() {
+        if !world.contains(target) {
+            warn!(
+                "Entity {} is related to the entity {} which doesn't exist, dropping it from its \
+                 `{}`",
+                entity,
+                target,
+                type_name::<Relation<R>>()
+            );
+            commands.add(move |world| detach::<R>(world, entity, target));
+            continue;
+        }
+
+        world.relations.link(
+            target,
+            RelationEdge {
+                c_type: Relation::<R>::component_type(),
+                source: entity,
+                detach: detach::<R>,
+            },
+        );
+    }
+}
+
+/// Removes the targets of a [`Relation`] from the index before it's removed from an entity.
+fn unlink<R: RelationKind>(world: &World, entity: EntityId, _: &mut Commands) {
+    let Ok(relation) = world.get::<Relation<R>>(entity) else {
+        return;
+    };
+
+    for &target in relation.targets() {
+        world
+            .relations
+            .unlink(target, Relation::<R>::component_type(), entity);
+    }
+}
+
+/// Removes `target` from the [`Relation`] of `source`, removing the relation if it was the last
+/// one.
+fn detach<R: RelationKind>(world: &mut World, source: EntityId, target: EntityId) {
+    let Ok(relation) = world.get::<Relation<R>>(source) else {
+        return;
+    };
+
+    let targets: SmallVec<[EntityId; 1]> = relation
+        .targets()
+        .iter()
+        .copied()
+        .filter(|&t| t != target)
+        .collect();
+
+    // The source is alive since it was just read.
+    if targets.is_empty() {
+        let _ = world.remove::<Relation<R>>(source);
+    } else {
+        let _ = world.insert(source, Relation::<R>::many(targets));
+    }
+}
+
+/// An entity pointing at a target through a relation.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct RelationEdge {
+    /// The type of the [`Relation`] component.
+    c_type: ComponentType,
+    source: EntityId,
+    /// Removes the target from the relation of the source, used when the target is despawned.
+    detach: fn(&mut World, EntityId, EntityId),
+}
+
+/// The reverse index of relations, mapping each target to the entities pointing at it.
+///
+/// Note: it's kept up to date by the hooks of [`Relation`], which only get a shared reference to
+/// the world.
+#[derive(Debug, Default)]
+pub(super) struct RelationIndex(DashMap<EntityId, Vec<RelationEdge>, BuildHasherDefault<FxHasher>>);
+
+impl RelationIndex {
+    fn link(&self, target: EntityId, edge: RelationEdge) {
+        let mut edges = self.0.entry(target).or_default();
+
+        if !edges
+            .iter()
+            .any(|e| e.c_type == edge.c_type && e.source == edge.source)
+        {
+            edges.push(edge);
+        }
+    }
+
+    fn unlink(&self, target: EntityId, c_type: ComponentType, source: EntityId) {
+        let mut empty = false;
+
+        if let Some(mut edges) = self.0.get_mut(&target) {
+            edges.retain(|e| e.c_type != c_type || e.source != source);
+            empty = edges.is_empty();
+        }
+
+        if empty {
+            self.0.remove(&target);
+        }
+    }
+
+    /// Returns the entities pointing at `target` through the relation `c_type`.
+    fn sources(&self, target: EntityId, c_type: ComponentType) -> Vec<EntityId> {
+        self.0
+            .get(&target)
+            .map(|edges| {
+                edges
+                    .iter()
+                    .filter(|e| e.c_type == c_type)
+                    .map(|e| e.source)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Detaches every relation pointing at `target`.
+    pub(super) fn detach_all(world: &mut World, target: EntityId) {
+        let Some((_, edges)) = world.relations.0.remove(&target) else {
+            return;
+        };
+
+        for edge in edges {
+            (edge.detach)(world, edge.source, target);
+        }
+    }
+}
+
+impl World {
+    /// Adds `target` to the relation `R` of `source`, inserting the relation if needed.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use woody::ecs::component::Component;
+    /// use woody::ecs::relation::RelationKind;
+    /// use woody::ecs::world::World;
+    ///
+    /// #[derive(Component)]
+    /// struct Person(&'static str);
+    ///
+    /// struct Likes;
+    /// impl RelationKind for Likes {}
+    ///
+    /// let mut world = World::new();
+    /// let alice = world.spawn(Person("Alice"));
+    /// let bob = world.spawn(Person("Bob"));
+    /// let carol = world.spawn(Person("Carol"));
+    ///
+    /// world.relate::<Likes>(alice, bob).unwrap();
+    /// world.relate::<Likes>(alice, carol).unwrap();
+    /// world.relate::<Likes>(carol, bob).unwrap();
+    ///
+    /// assert_eq!(world.targets::<Likes>(alice).unwrap(), vec![bob, carol]);
+    /// assert_eq!(world.sources::<Likes>(bob), vec![alice, carol]);
+    /// ```
+    pub fn relate<R: RelationKind>(
+        &mut self,
+        source: EntityId,
+        target: EntityId,
+    ) -> Result<(), EntityError> {
+        self.entity_checked(target)?;
+
+        let mut targets = self.targets::<R>(source)?;
+        if targets.contains(&target) {
+            return Ok(());
+        }
+        targets.push(target);
+
+        self.insert(source, Relation::<R>::many(targets))
+    }
+
+    /// Removes `target` from the relation `R` of `source`, removing the relation if it was the
+    /// last target.
+    pub fn unrelate<R: RelationKind>(
+        &mut self,
+        source: EntityId,
+        target: EntityId,
+    ) -> Result<(), EntityError> {
+        self.entity_checked(source)?;
+        detach::<R>(self, source, target);
+
+        Ok(())
+    }
+
+    /// Returns the targets of the relation `R` of an entity, which is empty if it has no such
+    /// relation.
+    pub fn targets<R: RelationKind>(&self, source: EntityId) -> Result<Vec<EntityId>, EntityError> {
+        match self.get::<Relation<R>>(source) {
+            Ok(relation) => Ok(relation.targets().to_vec()),
+            Err(EntityError::MissingComponent(..)) => Ok(Vec::new()),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Returns the entities pointing at `target` through the relation `R`.
+    pub fn sources<R: RelationKind>(&self, target: EntityId) -> Vec<EntityId> {
+        self.relations
+            .sources(target, Relation::<R>::component_type())
+    }
+
+    /// Returns every entity reachable from `source` by following the relation `R`, in breadth
+    /// first order and without `source` itself.
+    ///
+    /// Note: each entity is visited once, so cycles are fine.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use woody::ecs::component::Component;
+    /// use woody::ecs::relation::Relation;
+    /// use woody::ecs::relation::RelationKind;
+    /// use woody::ecs::world::World;
+    ///
+    /// #[derive(Component)]
+    /// struct Transform;
+    ///
+    /// struct ChildOf;
+    /// impl RelationKind for ChildOf {}
+    ///
+    /// let mut world = World::new();
+    /// let root = world.spawn(Transform);
+    /// let arm = world.spawn(Relation::<ChildOf>::new(root));
+    /// let hand = world.spawn(Relation::<ChildOf>::new(arm));
+    ///
+    /// // Walks up to the root.
+    /// assert_eq!(world.traverse::<ChildOf>(hand), vec![arm, root]);
+    /// ```
+    pub fn traverse<R: RelationKind>(&self, source: EntityId) -> Vec<EntityId> {
+        self.walk(source, |entity| {
+            self.targets::<R>(entity).unwrap_or_default()
+        })
+    }
+
+    /// Returns every entity reaching `target` by following the relation `R`, in breadth first
+    /// order and without `target` itself.
+    ///
+    /// Note: each entity is visited once, so cycles are fine.
+    pub fn traverse_sources<R: RelationKind>(&self, target: EntityId) -> Vec<EntityId> {
+        self.walk(target, |entity| self.sources::<R>(entity))
+    }
+
+    fn walk(&self, start: EntityId, next: impl Fn(EntityId) -> Vec<EntityId>) -> Vec<EntityId> {
+        let mut visited = FxHashSet::default();
+        visited.insert(start);
+
+        let mut queue = VecDeque::from([start]);
+        let mut reached = Vec::new();
+
+        while let Some(entity) = queue.pop_front() {
+            for related in next(entity) {
+                if visited.insert(related) {
+                    reached.push(related);
+                    queue.push_back(related);
+                }
+            }
+        }
+
+        reached
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Position(u8);
+    impl Component for Position {}
+
+    #[test]
+    fn relations() {
+        struct ChildOf;
+        impl RelationKind for ChildOf {}
+
+        let mut world = World::new();
+
+        let root = world.spawn(Position(0));
+        let arm = world.spawn(Relation::<ChildOf>::new(root));
+        let hand = world.spawn(Relation::<ChildOf>::new(arm));
+        let leg = world.spawn(Position(1));
+        world.relate::<ChildOf>(leg, root).unwrap();
+
+        assert_eq!(world.sources::<ChildOf>(root), vec![arm, leg]);
+        assert_eq!(world.traverse::<ChildOf>(hand), vec![arm, root]);
+        assert_eq!(
+            world.traverse_sources::<ChildOf>(root),
+            vec![arm, leg, hand]
+        );
+
+        // Many targets.
+        world.relate::<ChildOf>(hand, leg).unwrap();
+        assert_eq!(world.targets::<ChildOf>(hand).unwrap(), vec![arm, leg]);
+        assert_eq!(world.sources::<ChildOf>(leg), vec![hand]);
+
+        world.unrelate::<ChildOf>(hand, leg).unwrap();
+        assert!(world.sources::<ChildOf>(leg).is_empty());
+
+        // Despawning the target detaches the relations pointing at it.
+        world.despawn(arm).unwrap();
+        assert!(!world.has::<Relation<ChildOf>>(hand).unwrap());
+        assert_eq!(world.sources::<ChildOf>(root), vec![leg]);
+
+        // Targets which don't exist are dropped.
+        let orphan = world.spawn(Relation::<ChildOf>::many([root, arm, 1000]));
+        assert_eq!(world.targets::<ChildOf>(orphan).unwrap(), vec![root]);
+        assert!(world.sources::<ChildOf>(arm).is_empty());
+        assert!(world.sources::<ChildOf>(1000).is_empty());
+        world.insert(orphan, Relation::<ChildOf>::new(arm)).unwrap();
+        assert!(!world.has::<Relation<ChildOf>>(orphan).unwrap());
+        assert_eq!(world.sources::<ChildOf>(root), vec![leg]);
+
+        // Despawning the source removes it from the index.
+        world.despawn(leg).unwrap();
+        assert!(world.sources::<ChildOf>(root).is_empty());
+        assert!(world.relate::<ChildOf>(hand, arm).is_err());
+    }
+}
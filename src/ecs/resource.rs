@@ -0,0 +1,104 @@
+use std::any::Any;
+use std::any::TypeId;
+use std::fmt;
+
+use rustc_hash::FxHashMap;
+
+use super::world::World;
+
+pub use ecs_macros::Resource;
+
+/// A unique piece of data stored in the [`World`] outside of any entity, like the game settings
+/// or the input state.
+///
+/// Example:
+/// ```no_run
+/// use woody::ecs::resource::Resource;
+/// use woody::ecs::world::World;
+///
+/// #[derive(Resource)]
+/// struct Score(u32);
+///
+/// let mut world = World::new();
+/// world.insert_resource(Score(0));
+///
+/// world.resource_mut::<Score>().unwrap().0 += 10;
+/// assert_eq!(world.resource::<Score>().unwrap().0, 10);
+/// ```
+pub trait Resource: Send + Sync + Any {}
+
+/// A resource along with its type name, used for debugging.
+struct ResourceData {
+    name: &'static str,
+    value: Box<dyn Any + Send + Sync>,
+}
+
+/// A storage of resources, one per type.
+#[derive(Default)]
+pub(super) struct Resources(FxHashMap<TypeId, ResourceData>);
+
+impl fmt::Debug for Resources {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.values().map(|resource| resource.name))
+            .finish()
+    }
+}
+
+impl World {
+    /// Inserts a resource, returning the previous one of the same type.
+    pub fn insert_resource<R: Resource>(&mut self, resource: R) -> Option<R> {
+        let previous = self.resources.0.insert(
+            TypeId::of::<R>(),
+            ResourceData {
+                name: std::any::type_name::<R>(),
+                value: Box::new(resource),
+            },
+        )?;
+
+        previous
+            .value
+            .downcast::<R>()
+            .ok()
+            .map(|previous| *previous)
+    }
+
+    /// Inserts the default resource `R` if there isn't one yet.
+    pub fn init_resource<R: Resource + Default>(&mut self) {
+        if !self.contains_resource::<R>() {
+            self.insert_resource(R::default());
+        }
+    }
+
+    /// Removes the resource `R` and returns it.
+    pub fn remove_resource<R: Resource>(&mut self) -> Option<R> {
+        let resource = self.resources.0.remove(&TypeId::of::<R>())?;
+
+        resource
+            .value
+            .downcast::<R>()
+            .ok()
+            .map(|resource| *resource)
+    }
+
+    /// Checks if there is a resource `R`.
+    pub fn contains_resource<R: Resource>(&self) -> bool {
+        self.resources.0.contains_key(&TypeId::of::<R>())
+    }
+
+    /// Gets a reference to the resource `R`.
+    pub fn resource<R: Resource>(&self) -> Option<&R> {
+        self.resources
+            .0
+            .get(&TypeId::of::<R>())
+            .and_then(|resource| resource.value.downcast_ref::<R>())
+    }
+
+    /// Gets a mutable reference to the resource `R`.
+    pub fn resource_mut<R: Resource>(&mut self) -> Option<&mut R> {
+        self.resources
+            .0
+            .get_mut(&TypeId::of::<R>())
+            .and_then(|resource| resource.value.downcast_mut::<R>())
+    }
+}
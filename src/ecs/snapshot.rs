@@ -0,0 +1,410 @@
+use std::any::Any;
+use std::fmt;
+
+use rustc_hash::FxHashMap;
+
+use super::component::Component;
+use super::component::ComponentType;
+use super::entity::EntityId;
+use super::world::World;
+
+/// A component value captured by a snapshot.
+type SnapshotValue = dyn Any + Send + Sync;
+
+/// Type erased functions to capture, compare and restore a registered component type.
+#[derive(Debug, Clone, Copy)]
+struct SnapshotFns {
+    name: &'static str,
+    capture: fn(&World, EntityId) -> Option<Box<SnapshotValue>>,
+    restore: fn(&mut World, EntityId, &SnapshotValue),
+    remove: fn(&mut World, EntityId),
+    /// Checks if the value stored in the entity is equal to the captured one.
+    matches: fn(&World, EntityId, &SnapshotValue) -> bool,
+    eq: fn(&SnapshotValue, &SnapshotValue) -> bool,
+}
+
+impl SnapshotFns {
+    fn new<T: Component + Clone + PartialEq>() -> Self {
+        Self {
+            name: std::any::type_name::<T>(),
+            capture: capture::<T>,
+            restore: restore::<T>,
+            remove: remove::<T>,
+            matches: matches::<T>,
+            eq: eq::<T>,
+        }
+    }
+}
+
+fn capture<T: Component + Clone>(world: &World, entity: EntityId) -> Option<Box<SnapshotValue>> {
+    world
+        .get::<T>(entity)
+        .ok()
+        .map(|component| Box::new(component.value().clone()) as Box<SnapshotValue>)
+}
+
+fn restore<T: Component + Clone>(world: &mut World, entity: EntityId, value: &SnapshotValue) {
+    if let Some(value) = value.downcast_ref::<T>() {
+        let _ = world.insert(entity, value.clone());
+    }
+}
+
+fn remove<T: Component>(world: &mut World, entity: EntityId) {
+    let _ = world.remove::<T>(entity);
+}
+
+fn matches<T: Component + PartialEq>(
+    world: &World,
+    entity: EntityId,
+    value: &SnapshotValue,
+) -> bool {
+    match (world.get::<T>(entity), value.downcast_ref::<T>()) {
+        (Ok(current), Some(value)) => current.value() == value,
+        _ => false,
+    }
+}
+
+fn eq<T: Component + PartialEq>(a: &SnapshotValue, b: &SnapshotValue) -> bool {
+    match (a.downcast_ref::<T>(), b.downcast_ref::<T>()) {
+        (Some(a), Some(b)) => a == b,
+        _ => false,
+    }
+}
+
+/// The component types registered to be captured by snapshots.
+#[derive(Debug, Default)]
+pub(super) struct SnapshotRegistry(FxHashMap<ComponentType, SnapshotFns>);
+
+/// A component captured by a [`WorldSnapshot`].
+pub struct SnapshotComponent {
+    c_type: ComponentType,
+    value: Box<SnapshotValue>,
+    fns: SnapshotFns,
+}
+
+impl SnapshotComponent {
+    /// Returns the type of the captured component.
+    pub fn component_type(&self) -> ComponentType {
+        self.c_type
+    }
+
+    /// Returns the type name of the captured component.
+    pub fn name(&self) -> &'static str {
+        self.fns.name
+    }
+
+    /// Returns the captured value if it's a `T`.
+    pub fn get<T: Component>(&self) -> Option<&T> {
+        self.value.downcast_ref::<T>()
+    }
+}
+
+impl fmt::Debug for SnapshotComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.fns.name)
+    }
+}
+
+/// A copy of the components of every entity in a [`World`], taken with [`World::snapshot`].
+///
+/// Note: only the component types registered with [`World::register_snapshot`] are captured.
+#[derive(Debug, Default)]
+pub struct WorldSnapshot {
+    /// The captured components of each entity, indexed by [`EntityId`] like the world does.
+    entities: Vec<Option<Vec<SnapshotComponent>>>,
+}
+
+impl WorldSnapshot {
+    /// Checks if the entity was alive when the snapshot was taken.
+    pub fn contains(&self, entity: EntityId) -> bool {
+        self.components(entity).is_some()
+    }
+
+    /// Returns the ids of the entities alive when the snapshot was taken.
+    pub fn entities(&self) -> impl Iterator<Item = EntityId> + '_ {
+        self.entities
+            .iter()
+            .enumerate()
+            .filter(|(_, components)| components.is_some())
+            .map(|(entity, _)| entity as EntityId)
+    }
+
+    /// Returns the captured components of an entity, or `None` if it wasn't alive.
+    pub fn components(&self, entity: EntityId) -> Option<&[SnapshotComponent]> {
+        usize::try_from(entity)
+            .ok()
+            .and_then(|index| self.entities.get(index))
+            .and_then(|components| components.as_deref())
+    }
+
+    /// Returns the captured component `T` of an entity.
+    pub fn get<T: Component>(&self, entity: EntityId) -> Option<&T> {
+        self.components(entity)?
+            .iter()
+            .find(|component| component.c_type == T::component_type())?
+            .get::<T>()
+    }
+
+    /// Returns what changed from this snapshot to a `newer` one.
+    ///
+    /// Component changes are only reported for the entities alive in both snapshots.
+    ///
+    /// Example:
+    /// ```no_run
+    /// use woody::ecs::component::Component;
+    /// use woody::ecs::world::World;
+    ///
+    /// #[derive(Debug, Clone, PartialEq, Component)]
+    /// struct Health(u32);
+    ///
+    /// let mut world = World::new();
+    /// world.register_snapshot::<Health>();
+    ///
+    /// let player = world.spawn(Health(100));
+    /// let before = world.snapshot();
+    ///
+    /// world.get_mut::<Health>(player).unwrap().0 = 80;
+    /// let enemy = world.spawn(Health(10));
+    ///
+    /// let diff = before.diff(&world.snapshot());
+    /// assert_eq!(diff.spawned, vec![enemy]);
+    /// assert_eq!(diff.changed[0].entity, player);
+    ///
+    /// // Rollback.
+    /// world.restore(&before);
+    /// assert_eq!(world.get::<Health>(player).unwrap().0, 100);
+    /// assert!(!world.contains(enemy));
+    /// ```
+    pub fn diff(&self, newer: &WorldSnapshot) -> SnapshotDiff {
+        let mut diff = SnapshotDiff::default();
+
+        for index in 0..self.entities.len().max(newer.entities.len()) {
+            let entity = index as EntityId;
+
+            let (old, new) = match (self.components(entity), newer.components(entity)) {
+                (Some(old), Some(new)) => (old, new),
+                (None, Some(_)) => {
+                    diff.spawned.push(entity);
+                    continue;
+                }
+                (Some(_), None) => {
+                    diff.despawned.push(entity);
+                    continue;
+                }
+                (None, None) => continue,
+            };
+
+            for component in old {
+                let kind = match new.iter().find(|c| c.c_type == component.c_type) {
+                    None => ChangeKind::Removed,
+                    Some(c) if !(component.fns.eq)(&*component.value, &*c.value) => {
+                        ChangeKind::Modified
+                    }
+                    Some(_) => continue,
+                };
+
+                diff.changed
+                    .push(ComponentChange::new(entity, component, kind));
+            }
+
+            for component in new {
+                if !old.iter().any(|c| c.c_type == component.c_type) {
+                    diff.changed
+                        .push(ComponentChange::new(entity, component, ChangeKind::Added));
+                }
+            }
+        }
+
+        diff
+    }
+}
+
+/// The differences between two [`WorldSnapshot`]s, see [`WorldSnapshot::diff`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct SnapshotDiff {
+    /// Entities alive only in the newer snapshot.
+    pub spawned: Vec<EntityId>,
+    /// Entities alive only in the older snapshot.
+    pub despawned: Vec<EntityId>,
+    /// Components added, removed or modified in the entities alive in both snapshots.
+    pub changed: Vec<ComponentChange>,
+}
+
+impl SnapshotDiff {
+    /// Checks if nothing changed between the snapshots.
+    pub fn is_empty(&self) -> bool {
+        self.spawned.is_empty() && self.despawned.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// A component that changed between two snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComponentChange {
+    pub entity: EntityId,
+    pub c_type: ComponentType,
+    pub name: &'static str,
+    pub kind: ChangeKind,
+}
+
+impl ComponentChange {
+    fn new(entity: EntityId, component: &SnapshotComponent, kind: ChangeKind) -> Self {
+        Self {
+            entity,
+            c_type: component.c_type,
+            name: component.fns.name,
+            kind,
+        }
+    }
+}
+
+/// How a component changed between two snapshots.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl World {
+    /// Registers the component `T` to be captured by [`World::snapshot`].
+    pub fn register_snapshot<T: Component + Clone + PartialEq>(&mut self) {
+        self.snapshots
+            .0
+            .insert(T::component_type(), SnapshotFns::new::<T>());
+    }
+
+    /// Captures a copy of the registered components of every entity.
+    pub fn snapshot(&self) -> WorldSnapshot {
+        let entities = self
+            .entities
+            .iter()
+            .map(|entity| {
+                let entity = entity.as_ref()?;
+
+                let components = entity
+                    .components_types()
+                    .into_iter()
+                    .filter_map(|c_type| {
+                        let fns = *self.snapshots.0.get(&c_type)?;
+
+                        Some(SnapshotComponent {
+                            c_type,
+                            value: (fns.capture)(self, entity.id())?,
+                            fns,
+                        })
+                    })
+                    .collect();
+
+                Some(components)
+            })
+            .collect();
+
+        WorldSnapshot { entities }
+    }
+
+    /// Rolls the world back to a snapshot.
+    ///
+    /// Entities spawned after the snapshot are despawned and the despawned ones are spawned again
+    /// with the same id, then the registered components are added, removed or replaced to match
+    /// the snapshot. Components that weren't modified are left untouched so their hooks don't run.
+    ///
+    /// Note: components of types that aren't registered are left as they are, so entities spawned
+    /// again only get the registered ones.
+    pub fn restore(&mut self, snapshot: &WorldSnapshot) {
+        for index in 0..self.entities.len().max(snapshot.entities.len()) {
+            let entity = index as EntityId;
+
+            match (self.contains(entity), snapshot.contains(entity)) {
+                (true, false) => {
+                    let _ = self.despawn(entity);
+                }
+                (false, true) => self.spawn_empty_at(entity),
+                _ => {}
+            }
+        }
+
+        // Drop the entities past the snapshot that were just despawned, so new ids match again.
+        while self.entities.len() > snapshot.entities.len()
+            && matches!(self.entities.last(), Some(None))
+        {
+            self.entities.pop();
+        }
+
+        for (index, components) in snapshot.entities.iter().enumerate() {
+            let Some(components) = components else {
+                continue;
+            };
+            let entity = index as EntityId;
+
+            let Ok(stored) = self.entity_checked(entity) else {
+                continue;
+            };
+            let removed: Vec<SnapshotFns> = stored
+                .components_types()
+                .into_iter()
+                .filter(|c_type| !components.iter().any(|c| c.c_type == *c_type))
+                .filter_map(|c_type| self.snapshots.0.get(&c_type).copied())
+                .collect();
+
+            for fns in removed {
+                (fns.remove)(self, entity);
+            }
+
+            for component in components {
+                if !(component.fns.matches)(self, entity, &*component.value) {
+                    (component.fns.restore)(self, entity, &*component.value);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug)]
+    struct Position(u8);
+    impl Component for Position {}
+
+    #[test]
+    fn snapshot_and_restore() {
+        #[derive(Debug, Clone, PartialEq)]
+        struct Health(u8);
+        impl Component for Health {}
+
+        let mut world = World::new();
+        world.register_snapshot::<Health>();
+
+        let player = world.spawn((Position(0), Health(100)));
+        let enemy = world.spawn(Health(10));
+        let snapshot = world.snapshot();
+
+        assert!(snapshot.diff(&world.snapshot()).is_empty());
+        assert_eq!(snapshot.get::<Health>(player), Some(&Health(100)));
+        // Position isn't registered.
+        assert_eq!(snapshot.components(player).unwrap().len(), 1);
+
+        world.get_mut::<Health>(player).unwrap().0 = 50;
+        world.remove::<Health>(enemy).unwrap();
+        world.despawn(enemy).unwrap();
+        let spawned = world.spawn(Health(1));
+
+        let diff = snapshot.diff(&world.snapshot());
+        assert_eq!(diff.spawned, vec![spawned]);
+        assert_eq!(diff.despawned, vec![enemy]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].entity, player);
+        assert_eq!(diff.changed[0].kind, ChangeKind::Modified);
+
+        world.restore(&snapshot);
+        assert!(snapshot.diff(&world.snapshot()).is_empty());
+        assert_eq!(world.get::<Health>(player).unwrap().0, 100);
+        assert_eq!(world.get::<Position>(player).unwrap().0, 0);
+        assert_eq!(world.get::<Health>(enemy).unwrap().0, 10);
+        assert!(!world.contains(spawned));
+
+        // Ids are handed out again from where the snapshot left them.
+        assert_eq!(world.spawn(Health(1)), spawned);
+    }
+}
@@ -0,0 +1,212 @@
+use std::fmt;
+
+use rayon::iter::ParallelIterator;
+
+use super::archetype::ArchetypeId;
+use super::component::ComponentType;
+use super::query::Query;
+use super::world::World;
+
+/// A summary of what's stored in a [`World`], taken with [`World::stats`].
+///
+/// It's meant for debug overlays and log dumps, its [`Display`](fmt::Display) implementation
+/// prints one line per archetype and storage.
+///
+/// Example:
+/// ```no_run
+/// use woody::ecs::component::Component;
+/// use woody::ecs::world::World;
+///
+/// #[derive(Component)]
+/// struct Position(f32, f32);
+///
+/// let mut world = World::new();
+/// world.spawn(Position(0.0, 0.0));
+///
+/// let stats = world.stats();
+/// assert_eq!(stats.entities, 1);
+/// log::debug!("{stats}");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorldStats {
+    /// How many entities are alive.
+    pub entities: usize,
+    /// How many entity slots were left behind by despawned entities.
+    pub despawned: usize,
+    /// The archetypes, sorted by how many entities they have.
+    pub archetypes: Vec<ArchetypeStats>,
+    /// The component storages, sorted by how much memory they use.
+    pub storages: Vec<StorageStats>,
+}
+
+impl WorldStats {
+    /// Returns roughly how many bytes all component storages use.
+    pub fn memory(&self) -> usize {
+        self.storages.iter().map(|storage| storage.memory).sum()
+    }
+
+    /// Returns how many archetypes don't have any entity.
+    pub fn empty_archetypes(&self) -> usize {
+        self.archetypes
+            .iter()
+            .filter(|archetype| archetype.entities == 0)
+            .count()
+    }
+}
+
+impl fmt::Display for WorldStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "World: {} entities ({} despawned), {} archetypes ({} empty), {} storages using {} bytes",
+            self.entities,
+            self.despawned,
+            self.archetypes.len(),
+            self.empty_archetypes(),
+            self.storages.len(),
+            self.memory()
+        )?;
+
+        for archetype in &self.archetypes {
+            writeln!(
+                f,
+                "  archetype {:032x}: {} entities [{}]",
+                archetype.id,
+                archetype.entities,
+                archetype.components.join(", ")
+            )?;
+        }
+
+        for storage in &self.storages {
+            writeln!(
+                f,
+                "  storage {}: {} components in {} slots, {} bytes",
+                storage.name, storage.components, storage.slots, storage.memory
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A summary of an [`Archetype`](super::archetype::Archetype).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchetypeStats {
+    pub id: ArchetypeId,
+    /// How many entities have this archetype.
+    pub entities: usize,
+    /// The type names of the archetype components.
+    pub components: Vec<&'static str>,
+}
+
+/// A summary of a [`ComponentStorage`](super::component::ComponentStorage).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageStats {
+    pub c_type: ComponentType,
+    /// The component type name.
+    pub name: &'static str,
+    /// How many components are stored.
+    pub components: usize,
+    /// How many slots the storage has, including the ones left empty by removed components.
+    pub slots: usize,
+    /// Roughly how many bytes the storage uses.
+    pub memory: usize,
+}
+
+impl World {
+    /// Returns a summary of the entities, archetypes and component storages of this world.
+    pub fn stats(&self) -> WorldStats {
+        let entities = self.entities.iter().filter(|e| e.is_some()).count();
+
+        let mut archetypes: Vec<_> = self
+            .archetypes
+            .iter()
+            .map(|archetype| ArchetypeStats {
+                id: archetype.id(),
+                entities: archetype.entities().len(),
+                components: archetype
+                    .components_types()
+                    .iter()
+                    .map(|&c_type| self.components.name(c_type).unwrap_or("<unknown>"))
+                    .collect(),
+            })
+            .collect();
+        archetypes.sort_by(|a, b| b.entities.cmp(&a.entities).then(a.id.cmp(&b.id)));
+
+        let mut storages: Vec<_> = self
+            .components
+            .iter()
+            .map(|storage| StorageStats {
+                c_type: storage.component_type(),
+                name: storage.name(),
+                components: storage.count(),
+                slots: storage.slots(),
+                memory: storage.memory(),
+            })
+            .collect();
+        storages.sort_by(|a, b| b.memory.cmp(&a.memory).then(a.name.cmp(b.name)));
+
+        WorldStats {
+            entities,
+            despawned: self.entities.len() - entities,
+            archetypes,
+            storages,
+        }
+    }
+
+    /// Returns how many entities the query `Q` would match, without borrowing the world mutably.
+    pub fn query_matches<Q: Query>(&self) -> usize {
+        let c_types = Q::components_types();
+
+        self.archetypes
+            .iter()
+            .filter(|archetype| archetype.contains_c_types(&c_types))
+            .map(|archetype| archetype.entities().len())
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecs::component::Component;
+
+    #[derive(Debug)]
+    struct Position(u8);
+    impl Component for Position {}
+
+    #[derive(Debug)]
+    struct Velocity(u8);
+    impl Component for Velocity {}
+
+    #[test]
+    fn stats() {
+        let mut world = World::new();
+
+        world.spawn((Position(0), Velocity(0)));
+        world.spawn((Position(1), Velocity(1)));
+        let despawned = world.spawn(Position(2));
+        world.despawn(despawned).unwrap();
+
+        let stats = world.stats();
+        assert_eq!(stats.entities, 2);
+        assert_eq!(stats.despawned, 1);
+        assert_eq!(stats.archetypes.len(), 2);
+        assert_eq!(stats.archetypes[0].entities, 2);
+        assert_eq!(stats.empty_archetypes(), 1);
+        assert_eq!(stats.storages.len(), 2);
+
+        let position = stats
+            .storages
+            .iter()
+            .find(|storage| storage.name.ends_with("Position"))
+            .unwrap();
+        assert_eq!(position.components, 2);
+        assert_eq!(position.slots, 3);
+        assert!(position.memory > 0);
+
+        assert_eq!(world.query_matches::<&Position>(), 2);
+        assert_eq!(world.query_matches::<(&Position, &Velocity)>(), 2);
+        assert_eq!(world.query::<&Velocity>().len(), 2);
+    }
+}
@@ -1,32 +1,71 @@
 use super::archetype::Archetype;
+use super::archetype::ArchetypeId;
 use super::archetype::Archetypes;
+use super::archetype::EdgeKind;
+use super::command::Commands;
 use super::component::Bundle;
+use super::component::Component;
+use super::component::ComponentType;
 use super::component::Components;
 use super::entity::Entity;
+use super::entity::EntityError;
+use super::entity::EntityId;
+use super::entity::EntityMut;
+use super::entity::EntityRef;
+use super::hook::ComponentHook;
+use super::hook::ComponentHooks;
+use super::hook::HookKind;
+use super::name::NameIndex;
+use super::query::ComponentMut;
+use super::query::ComponentRef;
 use super::query::Query;
 use super::query::QueryState;
+use super::relation::RelationIndex;
+use super::resource::Resources;
+use super::snapshot::SnapshotRegistry;
 
 /// The ECS world where all entities and components will be stored.
 #[derive(Debug, Default)]
 pub struct World {
     /// All entities stored in this world.
-    pub(super) entities: Vec<Entity>,
+    ///
+    /// Note: despawned entities leave a `None` behind so the ids of the others stay valid.
+    pub(super) entities: Vec<Option<Entity>>,
     /// All entity archetypes stored in this world.
     pub(super) archetypes: Archetypes,
     /// Our storages where which component will go.
     ///
     /// Note: each component has it's own storage.
     pub(super) components: Components,
+    /// Hooks invoked when components are added, inserted or removed.
+    pub(super) hooks: ComponentHooks,
+    /// The reverse index of relations, see [`Relation`](super::relation::Relation).
+    pub(super) relations: RelationIndex,
+    /// The component types captured by snapshots.
+    pub(super) snapshots: SnapshotRegistry,
+    /// The index of entity names, see [`Name`](super::name::Name).
+    pub(super) names: NameIndex,
+    /// Data stored outside of entities, see [`Resource`](super::resource::Resource).
+    pub(super) resources: Resources,
 }
 
 impl World {
     /// Creates a new instance of [`World`].
+    ///
+    /// Note: nothing is reserved up front, storages grow as entities are spawned. If you already
+    /// know how much you're going to spawn use [`World::with_capacity`] or [`World::builder`].
     pub fn new() -> Self {
-        Self {
-            entities: Vec::with_capacity(100_000_000),
-            archetypes: Archetypes::with_capacity(100_000),
-            components: Components::with_capacity(100_000),
-        }
+        Self::default()
+    }
+
+    /// Creates a new instance of [`World`] with room for `entities` entities.
+    pub fn with_capacity(entities: usize) -> Self {
+        Self::builder().entities(entities).build()
+    }
+
+    /// Returns a [`WorldBuilder`] to configure how much the world reserves up front.
+    pub fn builder() -> WorldBuilder {
+        WorldBuilder::default()
     }
 
     /// Spawns an entity in world.
@@ -47,36 +86,578 @@ impl World {
     ///
     /// world.spawn((Position(0, 0,), Velocity(10)));
     /// ```
-    pub fn spawn<B: Bundle>(&mut self, entity: B) {
+    pub fn spawn<B: Bundle>(&mut self, entity: B) -> EntityId {
         let entity_index = self.entities.len();
 
         let c_types = B::components_types();
+        let archetype_id = self.prepare_archetype::<B>(&c_types, 1);
+
+        // SAFETY: the archetype was created in `prepare_archetype`.
+        unsafe { self.archetypes.archetype_mut_unchecked(archetype_id) }
+            .entities_mut()
+            .push(entity_index);
+
+        let mut c_ptrs = Vec::with_capacity(c_types.len());
+        entity.store_components(&mut self.components, &mut c_ptrs);
+
+        let entity = Entity::new(entity_index as EntityId, archetype_id, c_ptrs);
+
+        self.entities.push(Some(entity));
+        self.cache_name(entity_index as EntityId, &c_types);
+
+        let mut commands = Commands::default();
+        self.trigger_stored(entity_index as EntityId, &c_types, &c_types, &mut commands);
+        commands.apply(self);
+
+        entity_index as EntityId
+    }
+
+    /// Spawns every entity yielded by `batch` and returns their ids in the same order.
+    ///
+    /// The archetype and the component storages are resolved only once for the whole batch, and
+    /// the storages reserve room for the iterator's lower size hint before inserting.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// use woody::ecs::world::World;
+    /// use woody::ecs::component::Component;
+    ///
+    /// #[derive(Debug, Component)]
+    /// struct Position(i64, i64);
+    ///
+    /// let mut world = World::new();
+    ///
+    /// let ids = world.spawn_batch((0..1000).map(|i| Position(i, i)));
+    ///
+    /// assert_eq!(ids.len(), 1000);
+    /// ```
+    pub fn spawn_batch<B, I>(&mut self, batch: I) -> Vec<EntityId>
+    where
+        B: Bundle,
+        I: IntoIterator<Item = B>,
+    {
+        let mut batch = batch.into_iter().peekable();
+
+        // Nothing is registered for an empty batch, not even its archetype.
+        if batch.peek().is_none() {
+            return Vec::new();
+        }
+
+        let (additional, _) = batch.size_hint();
+
+        let c_types = B::components_types();
+        let archetype_id = self.prepare_archetype::<B>(&c_types, additional);
+
+        self.entities.reserve(additional);
+
+        let mut ids = Vec::with_capacity(additional);
+
+        {
+            // SAFETY: the archetype was created in `prepare_archetype`.
+            let mut archetype = unsafe { self.archetypes.archetype_mut_unchecked(archetype_id) };
+
+            for bundle in batch {
+                let entity_index = self.entities.len();
+
+                let mut c_ptrs = Vec::with_capacity(c_types.len());
+                bundle.store_components(&mut self.components, &mut c_ptrs);
+
+                archetype.entities_mut().push(entity_index);
+                self.entities.push(Some(Entity::new(
+                    entity_index as EntityId,
+                    archetype_id,
+                    c_ptrs,
+                )));
+
+                ids.push(entity_index as EntityId);
+            }
+        }
+
+        for &id in ids.iter() {
+            self.cache_name(id, &c_types);
+        }
+
+        // Hooks only run once the whole batch is stored, so they can see every entity of it.
+        let mut commands = Commands::default();
+        for &id in ids.iter() {
+            self.trigger_stored(id, &c_types, &c_types, &mut commands);
+        }
+        commands.apply(self);
+
+        ids
+    }
+
+    /// Inserts components into an entity, replacing the ones it already has.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// use woody::ecs::world::World;
+    /// use woody::ecs::component::Component;
+    ///
+    /// #[derive(Debug, Component)]
+    /// struct Health(u8);
+    ///
+    /// #[derive(Debug, Component)]
+    /// struct Shield(u8);
+    ///
+    /// let mut world = World::new();
+    /// let player = world.spawn(Health(100));
+    ///
+    /// world.insert(player, (Health(50), Shield(10))).unwrap();
+    /// ```
+    pub fn insert<B: Bundle>(&mut self, entity: EntityId, bundle: B) -> Result<(), EntityError> {
+        let c_types = B::components_types();
+
+        let (replaced, added): (Vec<ComponentType>, Vec<ComponentType>) = {
+            let stored = self.entity_checked(entity)?;
+
+            c_types
+                .iter()
+                .partition(|&&c_type| stored.c_ptr(c_type).is_some())
+        };
 
-        let archetype_id = Archetype::id_from_c_types(&c_types);
-        let mut archetype = self.archetypes.insert(archetype_id, &c_types);
+        let mut commands = Commands::default();
+        self.hooks
+            .trigger(HookKind::Remove, &replaced, self, entity, &mut commands);
 
-        archetype.entities_mut().push(entity_index);
+        B::register_components(&mut self.components, &mut self.hooks);
+
+        // SAFETY: we've already checked that the entity exists, and hooks can't despawn it.
+        let stored = unsafe {
+            self.entities
+                .get_unchecked_mut(entity as usize)
+                .as_mut()
+                .unwrap_unchecked()
+        };
+        bundle.store_components(&mut self.components, stored.c_ptrs_mut());
+
+        self.migrate(entity, &added, EdgeKind::Add);
+        self.cache_name(entity, &c_types);
+
+        self.trigger_stored(entity, &added, &c_types, &mut commands);
+        commands.apply(self);
+
+        Ok(())
+    }
+
+    /// Removes the components of bundle `B` from an entity, the ones it doesn't have are ignored.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// use woody::ecs::world::World;
+    /// use woody::ecs::component::Component;
+    ///
+    /// #[derive(Debug, Component)]
+    /// struct Health(u8);
+    ///
+    /// #[derive(Debug, Component)]
+    /// struct Shield(u8);
+    ///
+    /// let mut world = World::new();
+    /// let player = world.spawn((Health(100), Shield(10)));
+    ///
+    /// world.remove::<Shield>(player).unwrap();
+    /// ```
+    pub fn remove<B: Bundle>(&mut self, entity: EntityId) -> Result<(), EntityError> {
+        let removed: Vec<ComponentType> = {
+            let stored = self.entity_checked(entity)?;
+
+            B::components_types()
+                .into_iter()
+                .filter(|&c_type| stored.c_ptr(c_type).is_some())
+                .collect()
+        };
+
+        if removed.is_empty() {
+            return Ok(());
+        }
+
+        let mut commands = Commands::default();
+        self.hooks
+            .trigger(HookKind::Remove, &removed, self, entity, &mut commands);
+
+        // SAFETY: we've already checked that the entity exists, and hooks can't despawn it.
+        let stored = unsafe {
+            self.entities
+                .get_unchecked_mut(entity as usize)
+                .as_mut()
+                .unwrap_unchecked()
+        };
+
+        stored.c_ptrs_mut().retain(|&(c_type, index)| {
+            if !removed.contains(&c_type) {
+                return true;
+            }
+
+            if let Some(mut storage) = self.components.storage_mut(c_type) {
+                storage.remove(index);
+            }
+
+            false
+        });
+
+        self.migrate(entity, &removed, EdgeKind::Remove);
+        self.cache_name(entity, &removed);
+
+        commands.apply(self);
+
+        Ok(())
+    }
+
+    /// Registers a hook invoked when a component `T` is added to an entity that didn't have it,
+    /// either by spawning or inserting.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// use woody::ecs::command::Commands;
+    /// use woody::ecs::component::Component;
+    /// use woody::ecs::entity::EntityId;
+    /// use woody::ecs::world::World;
+    ///
+    /// #[derive(Debug, Component)]
+    /// struct Mesh(Vec<f32>);
+    ///
+    /// #[derive(Debug, Component)]
+    /// struct GpuBuffer(usize);
+    ///
+    /// fn upload_mesh(world: &World, entity: EntityId, commands: &mut Commands) {
+    ///     let len = world.get::<Mesh>(entity).unwrap().0.len();
+    ///
+    ///     commands.insert(entity, GpuBuffer(len));
+    /// }
+    ///
+    /// let mut world = World::new();
+    /// world.on_add::<Mesh>(upload_mesh);
+    ///
+    /// let entity = world.spawn(Mesh(vec![0.0; 9]));
+    ///
+    /// assert!(world.has::<GpuBuffer>(entity).unwrap());
+    /// ```
+    pub fn on_add<T: Component>(&mut self, hook: ComponentHook) {
+        self.hooks.add::<T>(HookKind::Add, hook);
+    }
+
+    /// Registers a hook invoked whenever a component `T` is stored in an entity, either added or
+    /// replacing the previous value.
+    pub fn on_insert<T: Component>(&mut self, hook: ComponentHook) {
+        self.hooks.add::<T>(HookKind::Insert, hook);
+    }
+
+    /// Registers a hook invoked right before a component `T` is removed from an entity, either by
+    /// removing, replacing or despawning.
+    pub fn on_remove<T: Component>(&mut self, hook: ComponentHook) {
+        self.hooks.add::<T>(HookKind::Remove, hook);
+    }
+
+    /// Gets an entity, returning an error if it was never spawned or has been despawned.
+    pub(super) fn entity_checked(&self, entity: EntityId) -> Result<&Entity, EntityError> {
+        usize::try_from(entity)
+            .ok()
+            .and_then(|index| self.entities.get(index))
+            .ok_or(EntityError::NotFound(entity))?
+            .as_ref()
+            .ok_or(EntityError::Despawned(entity))
+    }
+
+    /// Gets an entity but unwraps it.
+    ///
+    /// SAFETY: you must guarantee that the entity exists and wasn't despawned.
+    pub(super) unsafe fn entity_unchecked(&self, entity: EntityId) -> &Entity {
+        self.entities
+            .get_unchecked(entity as usize)
+            .as_ref()
+            .unwrap_unchecked()
+    }
+
+    /// Gets the storage index of the component `T` of an entity.
+    fn component_index<T: Component>(&self, entity: EntityId) -> Result<usize, EntityError> {
+        self.entity_checked(entity)?
+            .c_ptr(T::component_type())
+            .ok_or(EntityError::MissingComponent(
+                entity,
+                std::any::type_name::<T>(),
+            ))
+    }
+
+    /// Invokes the hooks of the components added and inserted into an entity.
+    fn trigger_stored(
+        &self,
+        entity: EntityId,
+        added: &[ComponentType],
+        inserted: &[ComponentType],
+        commands: &mut Commands,
+    ) {
+        self.hooks
+            .trigger(HookKind::Add, added, self, entity, commands);
+        self.hooks
+            .trigger(HookKind::Insert, inserted, self, entity, commands);
+    }
+
+    /// Moves an entity into the archetype reached by adding or removing `c_types` from its current
+    /// one, following the cached archetype edges.
+    fn migrate(&mut self, entity: EntityId, c_types: &[ComponentType], kind: EdgeKind) {
+        // SAFETY: only called for entities that exist.
+        let stored = unsafe {
+            self.entities
+                .get_unchecked_mut(entity as usize)
+                .as_mut()
+                .unwrap_unchecked()
+        };
+
+        let previous = stored.archetype_id();
+        let archetype_id = self.archetypes.transition_many(previous, c_types, kind);
+
+        if previous == archetype_id {
+            return;
+        }
+
+        stored.set_archetype_id(archetype_id);
+
+        self.detach_from_archetype(previous, entity);
+        // SAFETY: the archetype was created by the transition.
+        unsafe { self.archetypes.archetype_mut_unchecked(archetype_id) }
+            .entities_mut()
+            .push(entity as usize);
+    }
+
+    /// Spawns an entity without components with the given id, which must not be alive.
+    pub(super) fn spawn_empty_at(&mut self, entity: EntityId) {
+        let index = entity as usize;
+        if self.entities.len() <= index {
+            self.entities.resize_with(index + 1, || None);
+        }
+
+        let archetype_id = Archetype::id_from_c_types(&[]);
+        self.archetypes
+            .insert(archetype_id, &[])
+            .entities_mut()
+            .push(index);
+
+        self.entities[index] = Some(Entity::new(entity, archetype_id, Vec::new()));
+    }
+
+    /// Removes an entity from the entities list of an archetype.
+    fn detach_from_archetype(&mut self, archetype_id: ArchetypeId, entity: EntityId) {
+        if let Some(mut archetype) = self.archetypes.archetype_mut(archetype_id) {
+            let entities = archetype.entities_mut();
+
+            if let Some(position) = entities.iter().position(|&e| e as EntityId == entity) {
+                entities.swap_remove(position);
+            }
+        }
+    }
+
+    /// Makes sure the archetype and a [`ComponentStorage`](super::component::ComponentStorage)
+    /// for each of the component types exist, reserving room for `additional` entities in them.
+    fn prepare_archetype<B: Bundle>(
+        &mut self,
+        c_types: &[ComponentType],
+        additional: usize,
+    ) -> ArchetypeId {
+        let archetype_id = Archetype::id_from_c_types(c_types);
+
+        self.archetypes
+            .insert(archetype_id, c_types)
+            .entities_mut()
+            .reserve(additional);
 
         // Create a ComponentStorage for each new component.
+        B::register_components(&mut self.components, &mut self.hooks);
+
         for &c_type in c_types.iter() {
-            let _ = self.components.insert(c_type);
+            if let Some(mut storage) = self.components.storage_mut(c_type) {
+                storage.reserve(additional);
+            }
         }
 
-        let mut c_ptrs = Vec::with_capacity(100);
-        entity.store_components(&mut self.components, &mut c_ptrs);
+        archetype_id
+    }
+
+    /// Despawns an entity, dropping all of its components and removing it from the relations
+    /// pointing at it.
+    pub fn despawn(&mut self, entity: EntityId) -> Result<(), EntityError> {
+        let c_types = self.entity_checked(entity)?.components_types();
+
+        let mut commands = Commands::default();
+        self.hooks
+            .trigger(HookKind::Remove, &c_types, self, entity, &mut commands);
 
-        let entity = Entity::new(entity_index as u128, archetype_id, c_ptrs);
+        // SAFETY: we've already checked that the entity exists, and hooks can't despawn it.
+        let entity = unsafe {
+            self.entities
+                .get_mut(entity as usize)
+                .unwrap_unchecked()
+                .take()
+                .unwrap_unchecked()
+        };
 
-        self.entities.push(entity);
+        self.detach_from_archetype(entity.archetype_id(), entity.id());
+
+        for &(c_type, index) in entity.c_ptrs() {
+            if let Some(mut storage) = self.components.storage_mut(c_type) {
+                storage.remove(index);
+            }
+        }
+
+        RelationIndex::detach_all(self, entity.id());
+
+        commands.apply(self);
+
+        Ok(())
     }
 
+    /// Checks if the entity exists and wasn't despawned.
+    pub fn contains(&self, entity: EntityId) -> bool {
+        self.entity_checked(entity).is_ok()
+    }
+
+    /// Returns a read only view of an entity.
+    pub fn entity(&self, entity: EntityId) -> Result<EntityRef<'_>, EntityError> {
+        let entity = self.entity_checked(entity)?;
+
+        Ok(EntityRef::new(self, entity))
+    }
+
+    /// Returns a mutable view of an entity.
+    pub fn entity_mut(&mut self, entity: EntityId) -> Result<EntityMut<'_>, EntityError> {
+        self.entity_checked(entity)?;
+
+        Ok(EntityMut::new(self, entity))
+    }
+
+    /// Checks if an entity has a component of type `T`.
+    pub fn has<T: Component>(&self, entity: EntityId) -> Result<bool, EntityError> {
+        Ok(self.entity(entity)?.has::<T>())
+    }
+
+    /// Gets a reference to the component `T` of an entity without running a query.
+    ///
+    /// Example:
+    ///
+    /// ```no_run
+    /// use woody::ecs::world::World;
+    /// use woody::ecs::component::Component;
+    ///
+    /// #[derive(Debug, Component)]
+    /// struct Health(u8);
+    ///
+    /// let mut world = World::new();
+    /// let player = world.spawn(Health(100));
+    ///
+    /// assert_eq!(world.get::<Health>(player).unwrap().0, 100);
+    /// ```
+    pub fn get<T: Component>(&self, entity: EntityId) -> Result<ComponentRef<'_, T>, EntityError> {
+        let c_ptr = self.component_index::<T>(entity)?;
+        // SAFETY: the entity has the component so its storage exists, and the returned reference
+        // keeps the world borrowed so nothing can mutate the storage.
+        let storage = unsafe { <&T as Query>::init_storage(&self.components) };
+
+        Ok(unsafe { <&T as Query>::fetch(storage, c_ptr) })
+    }
+
+    /// Gets a mutable reference to the component `T` of an entity without running a query.
+    pub fn get_mut<T: Component>(
+        &mut self,
+        entity: EntityId,
+    ) -> Result<ComponentMut<'_, T>, EntityError> {
+        let c_ptr = self.component_index::<T>(entity)?;
+        // SAFETY: the entity has the component so its storage exists, and the returned reference
+        // keeps the world mutably borrowed so nothing else can access the storage.
+        let storage = unsafe { <&mut T as Query>::init_storage(&self.components) };
+
+        Ok(unsafe { <&mut T as Query>::fetch(storage, c_ptr) })
+    }
+
+    /// Creates a [`QueryState`] over the entities matching `Q`.
+    ///
+    /// Note: the query keeps the world mutably borrowed, so two queries can't be alive at once,
+    /// which is what prevents them from handing out overlapping references:
+    ///
+    /// ```compile_fail,E0499
+    /// use woody::ecs::component::Component;
+    /// use woody::ecs::world::World;
+    ///
+    /// struct Position(u8);
+    /// impl Component for Position {}
+    ///
+    /// let mut world = World::new();
+    /// world.spawn(Position(0));
+    ///
+    /// let mut first = world.query::<&mut Position>();
+    /// let mut second = world.query::<&mut Position>();
+    ///
+    /// for (a, b) in first.iter().zip(second.iter()) {}
+    /// ```
     pub fn query<Q: Query>(&mut self) -> QueryState<'_, Q> {
         QueryState::new(self)
     }
 }
 
+/// Configures how much a [`World`] reserves up front.
+///
+/// Every capacity defaults to zero, which means storages only grow when needed.
+///
+/// Example:
+///
+/// ```no_run
+/// use woody::ecs::world::World;
+///
+/// let world = World::builder()
+///     .entities(10_000)
+///     .archetypes(64)
+///     .components(32)
+///     .build();
+/// ```
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WorldBuilder {
+    entities: usize,
+    archetypes: usize,
+    components: usize,
+}
+
+impl WorldBuilder {
+    /// Sets how many entities the world reserves.
+    pub fn entities(mut self, capacity: usize) -> Self {
+        self.entities = capacity;
+        self
+    }
+
+    /// Sets how many archetypes the world reserves.
+    pub fn archetypes(mut self, capacity: usize) -> Self {
+        self.archetypes = capacity;
+        self
+    }
+
+    /// Sets how many component storages the world reserves.
+    pub fn components(mut self, capacity: usize) -> Self {
+        self.components = capacity;
+        self
+    }
+
+    /// Creates the [`World`].
+    pub fn build(self) -> World {
+        World {
+            entities: Vec::with_capacity(self.entities),
+            archetypes: Archetypes::with_capacity(self.archetypes),
+            components: Components::with_capacity(self.components),
+            hooks: ComponentHooks::default(),
+            relations: RelationIndex::default(),
+            snapshots: SnapshotRegistry::default(),
+            names: NameIndex::default(),
+            resources: Resources::default(),
+        }
+    }
+}
+
+#[cfg(test)]
 mod test {
     use crate::ecs::component::Component;
+    use crate::ecs::entity::EntityError;
+    use crate::ecs::query::QueryError;
 
     #[derive(Debug)]
     struct Position(u8);
@@ -118,4 +699,153 @@ mod test {
             println!("{:?}, {:?}", velocity.value(), pos.value());
         }
     }
+
+    #[test]
+    fn spawn_batch() {
+        let mut world = super::World::with_capacity(10);
+
+        world.spawn(Position(0));
+        let ids = world.spawn_batch((1..=5).map(|i| (Velocity(i), Position(i))));
+
+        assert_eq!(ids, vec![1, 2, 3, 4, 5]);
+        // Checks if the whole batch went into a single archetype.
+        assert_eq!(world.archetypes.len(), 2);
+        assert_eq!(world.entities.len(), 6);
+
+        let mut query = world.query::<(&Velocity, &Position)>();
+        assert_eq!(query.iter().count(), 5);
+
+        let empty = world.spawn_batch(std::iter::empty::<Velocity>());
+        assert!(empty.is_empty());
+        assert_eq!(world.archetypes.len(), 2);
+        assert_eq!(world.components.len(), 2);
+    }
+
+    #[test]
+    fn get() {
+        let mut world = super::World::new();
+
+        let first = world.spawn((Velocity(0), Position(1)));
+        let second = world.spawn(Position(2));
+
+        assert_eq!(world.get::<Position>(first).unwrap().0, 1);
+        assert_eq!(world.get::<Velocity>(first).unwrap().0, 0);
+        assert_eq!(world.get::<Position>(second).unwrap().0, 2);
+
+        world.get_mut::<Position>(second).unwrap().0 = 10;
+        assert_eq!(world.get::<Position>(second).unwrap().0, 10);
+
+        assert_eq!(world.has::<Velocity>(first), Ok(true));
+        assert_eq!(world.has::<Velocity>(second), Ok(false));
+        assert!(matches!(
+            world.get::<Velocity>(second),
+            Err(EntityError::MissingComponent(_, _))
+        ));
+        assert!(matches!(
+            world.get::<Velocity>(42),
+            Err(EntityError::NotFound(42))
+        ));
+
+        let entity = world.entity(first).unwrap();
+        assert_eq!(entity.components_types().len(), 2);
+        assert_eq!(entity.components_names().len(), 2);
+    }
+
+    #[test]
+    fn despawn() {
+        let mut world = super::World::new();
+
+        let first = world.spawn(Position(0));
+        let second = world.spawn(Position(1));
+
+        assert_eq!(world.despawn(first), Ok(()));
+        assert_eq!(world.despawn(first), Err(EntityError::Despawned(first)));
+        assert!(!world.contains(first));
+        assert!(matches!(
+            world.get::<Position>(first),
+            Err(EntityError::Despawned(_))
+        ));
+
+        let mut query = world.query::<&Position>();
+        assert_eq!(query.iter().count(), 1);
+
+        assert_eq!(world.get::<Position>(second).unwrap().0, 1);
+    }
+
+    #[test]
+    fn get_many_mut() {
+        let mut world = super::World::new();
+
+        let attacker = world.spawn((Velocity(0), Position(10)));
+        let target = world.spawn((Velocity(0), Position(20)));
+        let other = world.spawn(Velocity(0));
+
+        let mut query = world.query::<&mut Position>();
+
+        {
+            let [mut a, mut t] = query.get_many_mut([attacker, target]).unwrap();
+            a.0 += 1;
+            t.0 -= 1;
+        }
+
+        assert!(matches!(
+            query.get_many_mut([attacker, attacker]),
+            Err(QueryError::Aliasing(_))
+        ));
+        assert!(matches!(
+            query.get_many_mut([attacker, other]),
+            Err(QueryError::NoMatch(_))
+        ));
+        assert!(matches!(
+            query.get_many_mut([target, 42]),
+            Err(QueryError::NoMatch(42))
+        ));
+
+        assert_eq!(world.get::<Position>(attacker).unwrap().0, 11);
+        assert_eq!(world.get::<Position>(target).unwrap().0, 19);
+    }
+
+    #[test]
+    #[should_panic(expected = "borrows component")]
+    fn query_conflicting_borrows() {
+        let mut world = super::World::new();
+
+        world.spawn((Velocity(0), Position(1)));
+
+        let _ = world.query::<(&mut Position, &Velocity, &Position)>();
+    }
+
+    #[test]
+    fn par_iter_mut() {
+        use rayon::prelude::ParallelIterator;
+
+        let mut world = super::World::new();
+
+        world.spawn_batch((0..1000).map(|_| (Velocity(1), Position(0))));
+
+        let mut query = world.query::<(&mut Position, &Velocity)>();
+        query.par_iter().for_each(|(mut position, velocity)| {
+            position.0 += velocity.0;
+        });
+
+        let mut query = world.query::<&Position>();
+        assert!(query.iter().all(|position| position.0 == 1));
+    }
+
+    #[test]
+    fn insert_and_remove() {
+        let mut world = super::World::new();
+
+        let entity = world.spawn(Position(0));
+
+        world.insert(entity, (Velocity(1), Position(2))).unwrap();
+        assert_eq!(world.get::<Position>(entity).unwrap().0, 2);
+        assert_eq!(world.get::<Velocity>(entity).unwrap().0, 1);
+        assert_eq!(world.query::<(&Velocity, &Position)>().iter().count(), 1);
+
+        world.remove::<Velocity>(entity).unwrap();
+        assert_eq!(world.has::<Velocity>(entity), Ok(false));
+        assert_eq!(world.query::<(&Velocity, &Position)>().iter().count(), 0);
+        assert_eq!(world.query::<&Position>().iter().count(), 1);
+    }
 }
@@ -3,11 +3,18 @@ use std::any::TypeId;
 use std::collections::hash_map::Entry;
 
 use ahash::HashMap;
+use serde::Deserialize;
+use serde::Serialize;
 
 use crate::app::GameState;
+use crate::condition::Condition;
+use crate::ecs::resource::Resource;
 use crate::ecs::world::World;
 
-pub trait Event: Copy + Send + Sync {}
+/// An event fired to the systems subscribed to it.
+///
+/// Note: each system gets its own clone of the event, so it should be cheap to clone.
+pub trait Event: Clone + Send + Sync {}
 
 pub trait Handler<E: Event> {
     fn handle(&self, world: &mut World, state: GameState, event: E);
@@ -29,17 +36,54 @@ pub(crate) struct InnerSystem {
 
 impl InnerSystem {
     pub fn handle<E: Event + 'static>(&mut self, world: &mut World, state: GameState, event: E) {
-        let handlers = self.handlers.get::<fn(&mut World, GameState, E)>();
+        let systems = self.handlers.get_mut::<System<E>>();
 
-        if let Some(handlers) = handlers {
-            for handler in handlers.iter() {
-                handler.handle(world, state, event);
+        if let Some(systems) = systems {
+            for system in systems {
+                if system.should_run(world, state) {
+                    system.handler.handle(world, state, event.clone());
+                }
             }
         }
     }
 
-    pub fn subscribe<E: Event + 'static>(&mut self, handler: fn(&mut World, GameState, E)) {
-        self.handlers.put(handler);
+    pub fn subscribe<E: Event + 'static>(
+        &mut self,
+        handler: fn(&mut World, GameState, E),
+    ) -> SystemConfig<'_, E> {
+        SystemConfig(self.handlers.put(System {
+            handler,
+            conditions: Vec::new(),
+        }))
+    }
+}
+
+/// A handler subscribed to the event `E` along with the conditions gating it.
+struct System<E: Event> {
+    handler: fn(&mut World, GameState, E),
+    conditions: Vec<Box<dyn Condition>>,
+}
+
+impl<E: Event> System<E> {
+    /// Checks if every condition of this system holds.
+    fn should_run(&mut self, world: &World, state: GameState) -> bool {
+        self.conditions
+            .iter_mut()
+            .all(|condition| condition.evaluate(world, state))
+    }
+}
+
+/// Configures a system right after it's subscribed.
+pub struct SystemConfig<'a, E: Event>(&'a mut System<E>);
+
+impl<'a, E: Event> SystemConfig<'a, E> {
+    /// Only runs the system when `condition` holds, if it's called more than once every condition
+    /// must hold.
+    ///
+    /// See [`States`](crate::state::States) for an example.
+    pub fn run_if(self, condition: impl Condition) -> Self {
+        self.0.conditions.push(Box::new(condition));
+        self
     }
 }
 
@@ -49,28 +93,27 @@ struct ErasedStorage {
 }
 
 impl ErasedStorage {
-    pub fn put<T: 'static>(&mut self, item: T) {
-        match self.items.entry(TypeId::of::<T>()) {
-            Entry::Occupied(mut entry) => entry.get_mut().push(Box::new(item)),
-            Entry::Vacant(entry) => {
-                entry.insert(vec![Box::new(item)]);
-            }
+    pub fn put<T: 'static>(&mut self, item: T) -> &mut T {
+        let items = match self.items.entry(TypeId::of::<T>()) {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(Vec::new()),
         };
-    }
+        items.push(Box::new(item));
 
-    pub fn get<T: 'static>(&self) -> Option<Vec<&T>> {
-        let erased = self.items.get(&TypeId::of::<T>());
+        items
+            .last_mut()
+            .and_then(|item| item.downcast_mut::<T>())
+            .unwrap()
+    }
 
-        if let Some(items) = erased {
-            return Some(
-                items
-                    .iter()
-                    .map(|item| item.downcast_ref::<T>().unwrap())
-                    .collect(),
-            );
-        }
+    pub fn get_mut<T: 'static>(&mut self) -> Option<impl Iterator<Item = &mut T>> {
+        let erased = self.items.get_mut(&TypeId::of::<T>())?;
 
-        None
+        Some(
+            erased
+                .iter_mut()
+                .map(|item| item.downcast_mut::<T>().unwrap()),
+        )
     }
 }
 
@@ -83,3 +126,68 @@ impl Event for CreateEvent {}
 #[derive(Debug, Clone, Copy)]
 pub struct UpdateEvent;
 impl Event for UpdateEvent {}
+
+/// Fired once when the app shuts down, right before it stops running, e.g. to save the game.
+#[derive(Debug, Clone, Copy)]
+pub struct ShutdownEvent;
+impl Event for ShutdownEvent {}
+
+/// Fired when the window gains or loses the focus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FocusChangedEvent {
+    pub focused: bool,
+}
+impl Event for FocusChangedEvent {}
+
+/// Fired when the app is sent to the background, e.g. to pause the audio.
+#[derive(Debug, Clone, Copy)]
+pub struct SuspendedEvent;
+impl Event for SuspendedEvent {}
+
+/// Fired when the app comes back from the background.
+///
+/// Note: it's also fired once when the app starts.
+#[derive(Debug, Clone, Copy)]
+pub struct ResumedEvent;
+impl Event for ResumedEvent {}
+
+/// Asks the app to quit, which it does at the end of the frame, firing this event and then the
+/// [`ShutdownEvent`].
+///
+/// Note: systems only get the world, so it's sent with [`AppExit::send`] and fired by the app.
+///
+/// Example:
+/// ```no_run
+/// use woody::app::GameState;
+/// use woody::ecs::world::World;
+/// use woody::event::AppExit;
+/// use woody::input::keyboard::KeyCode;
+/// use woody::input::keyboard::KeyboardEvent;
+///
+/// fn quit(world: &mut World, _: GameState, event: KeyboardEvent) {
+///     if event.keycode == KeyCode::Escape {
+///         AppExit.send(world);
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppExit;
+impl Event for AppExit {}
+
+impl AppExit {
+    /// Sends the event, which the app fires at the end of the frame before quitting.
+    pub fn send(self, world: &mut World) {
+        world.insert_resource(PendingExit(self));
+    }
+}
+
+/// The [`AppExit`] sent by the systems, waiting for the app to fire it.
+struct PendingExit(AppExit);
+impl Resource for PendingExit {}
+
+/// Takes the [`AppExit`] sent since the last call, if any.
+pub(crate) fn take_exit(world: &mut World) -> Option<AppExit> {
+    world
+        .remove_resource::<PendingExit>()
+        .map(|pending| pending.0)
+}
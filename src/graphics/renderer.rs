@@ -5,12 +5,15 @@ use glam::Mat4;
 use glam::Quat;
 use log::error;
 use thiserror::Error;
+use winit::dpi::PhysicalPosition;
+use winit::dpi::PhysicalSize;
 use winit::event_loop::EventLoop;
 use winit::window::Window;
-use winit::window::WindowBuilder;
 
 use super::vulkan::VulkanContext;
 use super::GraphicsError;
+use crate::input::text::Ime;
+use crate::window::WindowDescriptor;
 
 /// Renderer is a frontend that will be used by our systems.
 pub(crate) struct Renderer {
@@ -21,14 +24,14 @@ pub(crate) struct Renderer {
 }
 
 impl Renderer {
-    /// Create a new instance of [`Renderer`].
-    pub(crate) fn new(event_loop: &EventLoop<()>) -> Result<Self, RendererError> {
-        let window = WindowBuilder::new()
-            .with_title("Woody Engine")
-            .build(event_loop)?;
-        let window = Arc::new(window);
+    /// Create a new instance of [`Renderer`] along with the window described by `descriptor`.
+    pub(crate) fn new(
+        event_loop: &EventLoop<()>,
+        descriptor: &WindowDescriptor,
+    ) -> Result<Self, RendererError> {
+        let window = Arc::new(descriptor.build(event_loop)?);
 
-        let backend = VulkanContext::new(event_loop, window.clone())?;
+        let backend = VulkanContext::new(event_loop, window.clone(), descriptor.present_mode)?;
 
         let width = window.inner_size().width as f32;
         let height = window.inner_size().height as f32;
@@ -62,6 +65,18 @@ impl Renderer {
         self.view = view;
     }
 
+    /// Allows or disallows the input method editor of the window, and places its candidates box.
+    pub(crate) fn set_ime(&self, ime: &Ime) {
+        self.window.set_ime_allowed(ime.enabled);
+
+        if ime.enabled {
+            self.window.set_ime_cursor_area(
+                PhysicalPosition::new(ime.cursor_position.x, ime.cursor_position.y),
+                PhysicalSize::new(ime.cursor_size.x, ime.cursor_size.y),
+            );
+        }
+    }
+
     /// TODO: document this.
     pub(crate) fn draw_frame(&mut self) -> Result<(), RendererError> {
         let result = self.backend.begin_frame();
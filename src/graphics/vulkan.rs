@@ -55,6 +55,7 @@ use self::shaders::object::ObjectShader;
 use self::swapchain::SwapchainContext;
 
 use super::GraphicsError;
+use crate::window::PresentMode;
 
 mod buffer;
 mod command_buffer;
@@ -138,7 +139,11 @@ pub struct VulkanContext {
 
 impl VulkanContext {
     /// Creates a new [`VulkanContext`] instance.
-    pub fn new(event_loop: &EventLoop<()>, window: Arc<Window>) -> Result<Self, GraphicsError> {
+    pub fn new(
+        event_loop: &EventLoop<()>,
+        window: Arc<Window>,
+        present_mode: PresentMode,
+    ) -> Result<Self, GraphicsError> {
         let library = VulkanLibrary::new()?;
         let required_extensions = Surface::required_extensions(event_loop)?;
 
@@ -282,6 +287,7 @@ impl VulkanContext {
             surface.clone(),
             window.inner_size().width,
             window.inner_size().height,
+            present_mode,
         )?;
 
         let render_pass = RenderPass::new(
@@ -16,7 +16,7 @@ use vulkano::image::ImageType;
 use vulkano::image::ImageUsage;
 use vulkano::memory::allocator::StandardMemoryAllocator;
 use vulkano::swapchain::CompositeAlpha;
-use vulkano::swapchain::PresentMode;
+use vulkano::swapchain::PresentMode as vkPresentMode;
 use vulkano::swapchain::Surface;
 use vulkano::swapchain::Swapchain;
 use vulkano::swapchain::SwapchainCreateInfo;
@@ -25,6 +25,7 @@ use vulkano::sync::Sharing;
 use crate::graphics::vulkan::image::Image;
 use crate::graphics::vulkan::image::ImageCreateInfo;
 use crate::graphics::GraphicsError;
+use crate::window::PresentMode;
 
 const CANDIDATE_FORMATS: [Format; 3] = [
     Format::D32_SFLOAT,
@@ -51,8 +52,10 @@ impl SwapchainContext {
         surface: Arc<Surface>,
         width: u32,
         height: u32,
+        present_mode: PresentMode,
     ) -> Result<Self, GraphicsError> {
-        let (swapchain, images) = Self::create_swapchain(device.clone(), surface, width, height)?;
+        let (swapchain, images) =
+            Self::create_swapchain(device.clone(), surface, width, height, present_mode)?;
         let image_views = Self::create_swapchain_image_views(swapchain.clone(), &images)?;
 
         info!("Created swapchain with extent: ({}, {})", width, height);
@@ -137,6 +140,7 @@ impl SwapchainContext {
         surface: Arc<Surface>,
         width: u32,
         height: u32,
+        present_mode: PresentMode,
     ) -> Result<(Arc<Swapchain>, Vec<Arc<vkImage>>), GraphicsError> {
         let surface_capabilities = device
             .physical_device()
@@ -147,12 +151,22 @@ impl SwapchainContext {
             .physical_device()
             .surface_formats(&surface, Default::default())?[0];
 
-        // Check if Surface supports using Mailbox, if not use Fifo.
+        let requested = match present_mode {
+            PresentMode::Fifo => vkPresentMode::Fifo,
+            PresentMode::FifoRelaxed => vkPresentMode::FifoRelaxed,
+            PresentMode::Mailbox => vkPresentMode::Mailbox,
+            PresentMode::Immediate => vkPresentMode::Immediate,
+        };
+
+        // Check if Surface supports the requested present mode, if not use Fifo.
         let present_mode = device
             .physical_device()
             .surface_present_modes(&surface, Default::default())?
-            .find(|mode| *mode == PresentMode::Mailbox)
-            .unwrap_or(PresentMode::Fifo);
+            .find(|mode| *mode == requested)
+            .unwrap_or_else(|| {
+                info!("Present mode ({requested:?}) is not supported, using Fifo");
+                vkPresentMode::Fifo
+            });
 
         let (swapchain, images) = Swapchain::new(
             device,
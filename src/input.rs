@@ -1,10 +1,84 @@
 use glam::Vec2;
+use log::warn;
+use serde::Deserialize;
+use serde::Serialize;
 
+use crate::app::AppBuilder;
+use crate::ecs::resource::Resource;
+use crate::ecs::world::World;
 use crate::event::Event;
+use crate::plugin::Plugin;
 
+use self::button::ButtonInput;
+use self::gamepad::Gamepads;
+use self::keyboard::KeyCode;
+use self::text::Ime;
+
+pub mod action;
+pub mod button;
+pub mod gamepad;
 pub mod keyboard;
+pub mod replay;
+pub mod text;
+
+/// Inserts the input resources, which the app keeps up to date with the input events.
+///
+/// Note: without it, a warning is logged when the app is built. The keyboard and mouse events are
+/// still fired but nothing tracks them, so resources like [`ButtonInput`] or
+/// [`ActionMap`](self::action::ActionMap) stay empty, and the gamepad events are lost.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InputPlugin;
+
+impl Plugin for InputPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<ButtonInput<MouseButton>>()
+            .init_resource::<MouseScroll>()
+            .init_resource::<MouseMotion>()
+            .init_resource::<Ime>()
+            .init_resource::<Gamepads>();
+    }
+}
+
+/// Warns when the input resources are missing, since the input events then aren't tracked and the
+/// gamepad events are lost.
+pub(crate) fn check_resources(world: &World) {
+    if !world.contains_resource::<ButtonInput<KeyCode>>() || !world.contains_resource::<Gamepads>()
+    {
+        warn!("The input resources are missing, add the `InputPlugin` to track the input");
+    }
+}
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// Forgets the buttons just pressed or released, at the end of a frame.
+pub(crate) fn clear(world: &mut World) {
+    if let Some(keys) = world.resource_mut::<ButtonInput<KeyCode>>() {
+        keys.clear();
+    }
+    if let Some(buttons) = world.resource_mut::<ButtonInput<MouseButton>>() {
+        buttons.clear();
+    }
+    if let Some(scroll) = world.resource_mut::<MouseScroll>() {
+        *scroll = MouseScroll::default();
+    }
+    if let Some(motion) = world.resource_mut::<MouseMotion>() {
+        *motion = MouseMotion::default();
+    }
+    if let Some(gamepads) = world.resource_mut::<Gamepads>() {
+        gamepads.clear();
+    }
+}
+
+/// Releases every button, since their release is missed while the window isn't focused.
+pub(crate) fn release_all(world: &mut World) {
+    if let Some(keys) = world.resource_mut::<ButtonInput<KeyCode>>() {
+        keys.release_all();
+    }
+    if let Some(buttons) = world.resource_mut::<ButtonInput<MouseButton>>() {
+        buttons.release_all();
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct CursorEvent {
     pub x: f64,
     pub y: f64,
@@ -12,14 +86,116 @@ pub struct CursorEvent {
 
 impl Event for CursorEvent {}
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub struct MouseMotionEvent {
     pub delta: Vec2,
 }
 
+impl MouseMotionEvent {
+    /// Adds this motion to the [`MouseMotion`] of the frame.
+    pub(crate) fn track(&self, world: &mut World) {
+        if let Some(motion) = world.resource_mut::<MouseMotion>() {
+            motion.delta += self.delta;
+        }
+    }
+}
+
 impl Event for MouseMotionEvent {}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A resource holding how much the mouse moved during this frame.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MouseMotion {
+    pub delta: Vec2,
+}
+
+impl Resource for MouseMotion {}
+
+/// The unit of a scroll delta.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ScrollUnit {
+    /// Lines or rows to scroll, usually from a mouse wheel.
+    Line,
+    /// Pixels to scroll, usually from a touchpad.
+    Pixel,
+}
+
+/// A scroll of the mouse wheel or touchpad, positive `y` scrolls up and positive `x` scrolls
+/// right.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct MouseWheelEvent {
+    pub unit: ScrollUnit,
+    pub delta: Vec2,
+    /// Whether it comes from the device instead of the window.
+    ///
+    /// Note: the same scroll is usually reported by both, but the device keeps reporting while
+    /// the window isn't focused.
+    pub raw: bool,
+}
+
+impl MouseWheelEvent {
+    pub(crate) fn new(delta: winit::event::MouseScrollDelta, raw: bool) -> Self {
+        let (unit, delta) = match delta {
+            winit::event::MouseScrollDelta::LineDelta(x, y) => (ScrollUnit::Line, Vec2::new(x, y)),
+            winit::event::MouseScrollDelta::PixelDelta(position) => (
+                ScrollUnit::Pixel,
+                Vec2::new(position.x as f32, position.y as f32),
+            ),
+        };
+
+        Self { unit, delta, raw }
+    }
+
+    /// Adds this scroll to the [`MouseScroll`] of the frame, unless it comes from the device.
+    pub(crate) fn track(&self, world: &mut World) {
+        if self.raw {
+            return;
+        }
+
+        if let Some(scroll) = world.resource_mut::<MouseScroll>() {
+            match self.unit {
+                ScrollUnit::Line => scroll.line += self.delta,
+                ScrollUnit::Pixel => scroll.pixel += self.delta,
+            }
+        }
+    }
+}
+
+impl Event for MouseWheelEvent {}
+
+/// A resource holding how much the window was scrolled during this frame, e.g. to zoom the
+/// camera.
+///
+/// Example:
+/// ```no_run
+/// use woody::app::GameState;
+/// use woody::ecs::world::World;
+/// use woody::event::UpdateEvent;
+/// use woody::input::MouseScroll;
+///
+/// fn zoom(world: &mut World, state: GameState, _: UpdateEvent) {
+///     let scroll = world.resource::<MouseScroll>().unwrap();
+///     // Roughly 20 pixels per line.
+///     let amount = scroll.line.y + scroll.pixel.y / 20.0;
+/// }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct MouseScroll {
+    /// The lines scrolled.
+    pub line: Vec2,
+    /// The pixels scrolled.
+    pub pixel: Vec2,
+}
+
+impl MouseScroll {
+    /// Checks if nothing was scrolled.
+    pub fn is_zero(&self) -> bool {
+        self.line == Vec2::ZERO && self.pixel == Vec2::ZERO
+    }
+}
+
+impl Resource for MouseScroll {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MouseEvent {
     pub state: MouseState,
     pub button: MouseButton,
@@ -32,11 +208,23 @@ impl MouseEvent {
             button: button.into(),
         }
     }
+
+    /// Updates the [`ButtonInput`] of the mouse buttons with this event.
+    pub(crate) fn track(&self, world: &mut World) {
+        let Some(buttons) = world.resource_mut::<ButtonInput<MouseButton>>() else {
+            return;
+        };
+
+        match self.state {
+            MouseState::Pressed => buttons.press(self.button),
+            MouseState::Released => buttons.release(self.button),
+        }
+    }
 }
 
 impl Event for MouseEvent {}
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MouseState {
     Pressed,
     Released,
@@ -51,7 +239,7 @@ impl From<winit::event::ElementState> for MouseState {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum MouseButton {
     Left,
     Middle,
@@ -73,3 +261,39 @@ impl From<winit::event::MouseButton> for MouseButton {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use winit::dpi::PhysicalPosition;
+    use winit::event::MouseScrollDelta;
+
+    use super::*;
+
+    #[test]
+    fn mouse_scroll() {
+        let mut world = World::new();
+        world.init_resource::<MouseScroll>();
+
+        let line = MouseWheelEvent::new(MouseScrollDelta::LineDelta(0.0, 1.0), false);
+        assert_eq!(line.unit, ScrollUnit::Line);
+        assert_eq!(line.delta, Vec2::Y);
+
+        let delta = MouseScrollDelta::PixelDelta(PhysicalPosition::new(-3.0, 12.5));
+        let pixel = MouseWheelEvent::new(delta, false);
+        assert_eq!(pixel.unit, ScrollUnit::Pixel);
+        assert_eq!(pixel.delta, Vec2::new(-3.0, 12.5));
+
+        // The scrolls add up during the frame, except the ones of the device.
+        line.track(&mut world);
+        line.track(&mut world);
+        pixel.track(&mut world);
+        MouseWheelEvent::new(MouseScrollDelta::LineDelta(0.0, 5.0), true).track(&mut world);
+
+        let scroll = world.resource::<MouseScroll>().unwrap();
+        assert_eq!(scroll.line, Vec2::new(0.0, 2.0));
+        assert_eq!(scroll.pixel, Vec2::new(-3.0, 12.5));
+
+        clear(&mut world);
+        assert!(world.resource::<MouseScroll>().unwrap().is_zero());
+    }
+}
@@ -0,0 +1,586 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use glam::Vec2;
+use log::warn;
+use ron::ser::PrettyConfig;
+use serde::Deserialize;
+use serde::Serialize;
+use serde::Serializer;
+use thiserror::Error;
+
+use crate::ecs::resource::Resource;
+use crate::ecs::world::World;
+
+use super::button::ButtonInput;
+use super::keyboard::KeyCode;
+use super::MouseButton;
+use super::MouseMotion;
+
+/// A modifier key, matching both its left and right keys.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ModifierKey {
+    Shift,
+    Control,
+    Alt,
+    Super,
+}
+
+impl ModifierKey {
+    /// Returns the left and right keys of this modifier.
+    pub fn keys(self) -> [KeyCode; 2] {
+        match self {
+            ModifierKey::Shift => [KeyCode::ShiftLeft, KeyCode::ShiftRight],
+            ModifierKey::Control => [KeyCode::ControlLeft, KeyCode::ControlRight],
+            ModifierKey::Alt => [KeyCode::AltLeft, KeyCode::AltRight],
+            ModifierKey::Super => [KeyCode::SuperLeft, KeyCode::SuperRight],
+        }
+    }
+}
+
+/// A keyboard key or mouse button an action can be bound to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Button {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Modifier(ModifierKey),
+}
+
+impl Button {
+    fn pressed(self, input: &Inputs) -> bool {
+        match self {
+            Button::Key(key) => input.keys.pressed(key),
+            Button::Mouse(button) => input.buttons.pressed(button),
+            Button::Modifier(modifier) => input.keys.any_pressed(modifier.keys()),
+        }
+    }
+
+    fn value(self, input: &Inputs) -> f32 {
+        if self.pressed(input) {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+impl From<KeyCode> for Button {
+    fn from(value: KeyCode) -> Self {
+        Button::Key(value)
+    }
+}
+
+impl From<MouseButton> for Button {
+    fn from(value: MouseButton) -> Self {
+        Button::Mouse(value)
+    }
+}
+
+impl From<ModifierKey> for Button {
+    fn from(value: ModifierKey) -> Self {
+        Button::Modifier(value)
+    }
+}
+
+/// An input an action is bound to, which gives it a value every frame.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum Binding {
+    /// Buttons held down together, like `Control + KeyS`, which is `1` along `x` while they're
+    /// all held.
+    Chord(Vec<Button>),
+    /// A 1D axis along `x`, which is `-1` while `negative` is held and `1` while `positive` is.
+    Axis { negative: Button, positive: Button },
+    /// A 2D axis made of four buttons, like WASD, whose length is at most `1`.
+    Composite {
+        up: Button,
+        down: Button,
+        left: Button,
+        right: Button,
+    },
+    /// The mouse motion during the frame, with `y` pointing down.
+    MouseMotion,
+}
+
+impl Binding {
+    /// Binds a single key.
+    pub fn key(key: KeyCode) -> Self {
+        Binding::Chord(vec![Button::Key(key)])
+    }
+
+    /// Binds a single mouse button.
+    pub fn mouse(button: MouseButton) -> Self {
+        Binding::Chord(vec![Button::Mouse(button)])
+    }
+
+    /// Binds buttons held down together.
+    pub fn chord(buttons: impl IntoIterator<Item = impl Into<Button>>) -> Self {
+        Binding::Chord(buttons.into_iter().map(Into::into).collect())
+    }
+
+    /// Binds a 1D axis.
+    pub fn axis(negative: impl Into<Button>, positive: impl Into<Button>) -> Self {
+        Binding::Axis {
+            negative: negative.into(),
+            positive: positive.into(),
+        }
+    }
+
+    /// Binds a 2D axis to the W, A, S and D keys.
+    pub fn wasd() -> Self {
+        Binding::Composite {
+            up: Button::Key(KeyCode::KeyW),
+            down: Button::Key(KeyCode::KeyS),
+            left: Button::Key(KeyCode::KeyA),
+            right: Button::Key(KeyCode::KeyD),
+        }
+    }
+
+    /// Binds a 2D axis to the arrow keys.
+    pub fn arrows() -> Self {
+        Binding::Composite {
+            up: Button::Key(KeyCode::ArrowUp),
+            down: Button::Key(KeyCode::ArrowDown),
+            left: Button::Key(KeyCode::ArrowLeft),
+            right: Button::Key(KeyCode::ArrowRight),
+        }
+    }
+
+    fn value(&self, input: &Inputs) -> Vec2 {
+        match self {
+            Binding::Chord(buttons) => {
+                if !buttons.is_empty() && buttons.iter().all(|button| button.pressed(input)) {
+                    Vec2::X
+                } else {
+                    Vec2::ZERO
+                }
+            }
+            Binding::Axis { negative, positive } => {
+                Vec2::new(positive.value(input) - negative.value(input), 0.0)
+            }
+            Binding::Composite {
+                up,
+                down,
+                left,
+                right,
+            } => Vec2::new(
+                right.value(input) - left.value(input),
+                up.value(input) - down.value(input),
+            )
+            .clamp_length_max(1.0),
+            Binding::MouseMotion => input.motion,
+        }
+    }
+}
+
+/// The input state read by the bindings.
+struct Inputs<'a> {
+    keys: &'a ButtonInput<KeyCode>,
+    buttons: &'a ButtonInput<MouseButton>,
+    motion: Vec2,
+}
+
+/// A named action along with its bindings and state.
+#[derive(Debug, Clone)]
+struct Action {
+    name: String,
+    bindings: Vec<Binding>,
+    value: Vec2,
+    pressed: bool,
+    just_pressed: bool,
+    just_released: bool,
+}
+
+/// A resource mapping named actions to their bindings, so game code doesn't depend on the
+/// actual keys and they can be rebound.
+///
+/// Actions can be read as buttons with [`ActionMap::pressed`], as 1D axes with
+/// [`ActionMap::axis`] or as 2D axes with [`ActionMap::axis_2d`]. When many bindings of an action
+/// are active, the one with the biggest value wins.
+///
+/// The actions are updated right before the [`UpdateEvent`](crate::event::UpdateEvent).
+///
+/// Example:
+/// ```no_run
+/// use woody::app::App;
+/// use woody::app::GameState;
+/// use woody::ecs::world::World;
+/// use woody::event::UpdateEvent;
+/// use woody::input::action::ActionMap;
+/// use woody::input::action::Binding;
+/// use woody::input::action::Button;
+/// use woody::input::action::ModifierKey;
+/// use woody::input::keyboard::KeyCode;
+/// use woody::input::InputPlugin;
+/// use woody::input::MouseButton;
+///
+/// fn play(world: &mut World, state: GameState, _: UpdateEvent) {
+///     let actions = world.resource::<ActionMap>().unwrap();
+///
+///     let movement = actions.axis_2d("move") * state.delta_time as f32;
+///     if actions.just_pressed("jump") {}
+///     if actions.just_pressed("save") {}
+/// }
+///
+/// let mut actions = ActionMap::default();
+/// actions
+///     .insert("move", [Binding::wasd(), Binding::arrows()])
+///     .insert("jump", [Binding::key(KeyCode::Space), Binding::mouse(MouseButton::Right)])
+///     .insert("save", [Binding::chord([Button::from(ModifierKey::Control), KeyCode::KeyS.into()])]);
+///
+/// // Players' own bindings replace the default ones.
+/// actions.load("bindings.ron").ok();
+///
+/// let mut builder = App::builder();
+/// builder.add_plugin(InputPlugin).insert_resource(actions);
+/// builder.systems.subscribe(play);
+/// builder.run().unwrap();
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct ActionMap {
+    actions: Vec<Action>,
+}
+
+impl Resource for ActionMap {}
+
+impl ActionMap {
+    /// Adds an action, replacing the bindings of the action with the same name if there's one.
+    pub fn insert(
+        &mut self,
+        name: impl Into<String>,
+        bindings: impl IntoIterator<Item = Binding>,
+    ) -> &mut Self {
+        let name = name.into();
+        let bindings = bindings.into_iter().collect();
+
+        match self.action_mut(&name) {
+            Some(action) => action.bindings = bindings,
+            None => self.actions.push(Action {
+                name,
+                bindings,
+                value: Vec2::ZERO,
+                pressed: false,
+                just_pressed: false,
+                just_released: false,
+            }),
+        }
+
+        self
+    }
+
+    /// Removes an action, returning its bindings.
+    pub fn remove(&mut self, name: &str) -> Option<Vec<Binding>> {
+        let index = self.actions.iter().position(|action| action.name == name)?;
+
+        Some(self.actions.remove(index).bindings)
+    }
+
+    /// Adds a binding to an action.
+    pub fn bind(&mut self, name: &str, binding: Binding) -> Result<(), ActionError> {
+        self.action_mut(name)
+            .ok_or_else(|| ActionError::UnknownAction(name.to_string()))?
+            .bindings
+            .push(binding);
+
+        Ok(())
+    }
+
+    /// Replaces the bindings of an action, returning the previous ones.
+    pub fn rebind(
+        &mut self,
+        name: &str,
+        bindings: impl IntoIterator<Item = Binding>,
+    ) -> Result<Vec<Binding>, ActionError> {
+        let action = self
+            .action_mut(name)
+            .ok_or_else(|| ActionError::UnknownAction(name.to_string()))?;
+
+        Ok(std::mem::replace(
+            &mut action.bindings,
+            bindings.into_iter().collect(),
+        ))
+    }
+
+    /// Returns the bindings of an action.
+    pub fn bindings(&self, name: &str) -> Option<&[Binding]> {
+        self.action(name).map(|action| action.bindings.as_slice())
+    }
+
+    /// Returns the names of the actions in the order they were added.
+    pub fn actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.iter().map(|action| action.name.as_str())
+    }
+
+    /// Checks if any binding of an action is active.
+    pub fn pressed(&self, name: &str) -> bool {
+        self.action(name).is_some_and(|action| action.pressed)
+    }
+
+    /// Checks if an action became active during this frame.
+    pub fn just_pressed(&self, name: &str) -> bool {
+        self.action(name).is_some_and(|action| action.just_pressed)
+    }
+
+    /// Checks if an action stopped being active during this frame.
+    pub fn just_released(&self, name: &str) -> bool {
+        self.action(name).is_some_and(|action| action.just_released)
+    }
+
+    /// Returns the value of an action along `x`, usually between `-1` and `1`.
+    pub fn axis(&self, name: &str) -> f32 {
+        self.axis_2d(name).x
+    }
+
+    /// Returns the value of an action, whose length is usually at most `1`.
+    pub fn axis_2d(&self, name: &str) -> Vec2 {
+        self.action(name).map_or(Vec2::ZERO, |action| action.value)
+    }
+
+    /// Returns the bindings in the RON format, as a map from the action names to their bindings.
+    ///
+    /// Example:
+    /// ```text
+    /// {
+    ///     "jump": [
+    ///         Chord([Key(Space)]),
+    ///         Chord([Mouse(Right)]),
+    ///     ],
+    ///     "save": [
+    ///         Chord([Modifier(Control), Key(KeyS)]),
+    ///     ],
+    ///     "zoom": [
+    ///         Axis(negative: Key(Minus), positive: Key(Equal)),
+    ///     ],
+    ///     "look": [
+    ///         MouseMotion,
+    ///     ],
+    /// }
+    /// ```
+    pub fn to_config(&self) -> Result<String, ActionError> {
+        // One binding per line.
+        let config = PrettyConfig::default().depth_limit(2);
+
+        Ok(ron::ser::to_string_pretty(self, config)?)
+    }
+
+    /// Replaces the bindings of the actions listed in a config, see [`ActionMap::to_config`].
+    ///
+    /// Note: actions missing from the config keep their bindings, and the ones missing from this
+    /// map are skipped. Nothing changes if the config is invalid.
+    pub fn load_config(&mut self, config: &str) -> Result<(), ActionError> {
+        let config: HashMap<String, Vec<Binding>> = ron::from_str(config)?;
+
+        for (name, bindings) in config {
+            if self.rebind(&name, bindings).is_err() {
+                warn!("Skipping bindings of unknown action `{name}`");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes the bindings to a config file, see [`ActionMap::to_config`].
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ActionError> {
+        fs::write(path, self.to_config()?)?;
+
+        Ok(())
+    }
+
+    /// Reads the bindings from a config file, see [`ActionMap::load_config`].
+    pub fn load(&mut self, path: impl AsRef<Path>) -> Result<(), ActionError> {
+        let config = fs::read_to_string(path)?;
+
+        self.load_config(&config)
+    }
+
+    /// Updates the state of every action from the input of this frame.
+    pub(crate) fn update(
+        &mut self,
+        keys: &ButtonInput<KeyCode>,
+        buttons: &ButtonInput<MouseButton>,
+        motion: Vec2,
+    ) {
+        let input = Inputs {
+            keys,
+            buttons,
+            motion,
+        };
+
+        for action in &mut self.actions {
+            let value = action
+                .bindings
+                .iter()
+                .map(|binding| binding.value(&input))
+                .max_by(|a, b| a.length_squared().total_cmp(&b.length_squared()))
+                .unwrap_or_default();
+            let pressed = value != Vec2::ZERO;
+
+            action.just_pressed = pressed && !action.pressed;
+            action.just_released = !pressed && action.pressed;
+            action.pressed = pressed;
+            action.value = value;
+        }
+    }
+
+    fn action(&self, name: &str) -> Option<&Action> {
+        self.actions.iter().find(|action| action.name == name)
+    }
+
+    fn action_mut(&mut self, name: &str) -> Option<&mut Action> {
+        self.actions.iter_mut().find(|action| action.name == name)
+    }
+}
+
+/// Serializes the bindings as a map, in the order the actions were added.
+impl Serialize for ActionMap {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(
+            self.actions
+                .iter()
+                .map(|action| (&action.name, &action.bindings)),
+        )
+    }
+}
+
+/// Updates the [`ActionMap`] from the input resources, before the frame is updated.
+pub(crate) fn update(world: &mut World) {
+    let Some(mut actions) = world.remove_resource::<ActionMap>() else {
+        return;
+    };
+
+    let motion = world
+        .resource::<MouseMotion>()
+        .map_or(Vec2::ZERO, |motion| motion.delta);
+
+    match (
+        world.resource::<ButtonInput<KeyCode>>(),
+        world.resource::<ButtonInput<MouseButton>>(),
+    ) {
+        (Some(keys), Some(buttons)) => actions.update(keys, buttons, motion),
+        _ => actions.update(&ButtonInput::default(), &ButtonInput::default(), motion),
+    }
+
+    world.insert_resource(actions);
+}
+
+#[derive(Debug, Error)]
+pub enum ActionError {
+    #[error("Unknown action `{0}`")]
+    UnknownAction(String),
+
+    #[error("Could not write the bindings: {0}")]
+    Serialize(#[from] ron::Error),
+
+    #[error("Invalid bindings: {0}")]
+    Deserialize(#[from] ron::error::SpannedError),
+
+    #[error("Could not read or write the bindings: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::input::keyboard::NativeCode;
+
+    #[test]
+    fn actions() {
+        let mut actions = ActionMap::default();
+        actions
+            .insert("move", [Binding::wasd()])
+            .insert("look", [Binding::MouseMotion])
+            .insert(
+                "save",
+                [Binding::chord([
+                    Button::from(ModifierKey::Control),
+                    KeyCode::KeyS.into(),
+                ])],
+            );
+
+        let mut keys = ButtonInput::default();
+        let buttons = ButtonInput::default();
+
+        keys.press(KeyCode::KeyW);
+        keys.press(KeyCode::KeyD);
+        keys.press(KeyCode::KeyS);
+        actions.update(&keys, &buttons, Vec2::new(3.0, -2.0));
+        assert_eq!(actions.axis_2d("move"), Vec2::X);
+        assert_eq!(actions.axis_2d("look"), Vec2::new(3.0, -2.0));
+        // The chord isn't complete yet.
+        assert!(!actions.pressed("save"));
+
+        keys.press(KeyCode::ControlRight);
+        actions.update(&keys, &buttons, Vec2::ZERO);
+        assert!(actions.just_pressed("save"));
+
+        actions.update(&keys, &buttons, Vec2::ZERO);
+        assert!(actions.pressed("save"));
+        assert!(!actions.just_pressed("save"));
+
+        keys.release(KeyCode::ControlRight);
+        actions.update(&keys, &buttons, Vec2::ZERO);
+        assert!(actions.just_released("save"));
+        assert!(!actions.pressed("unknown"));
+
+        actions.rebind("save", [Binding::key(KeyCode::F5)]).unwrap();
+        assert!(actions.bind("unknown", Binding::MouseMotion).is_err());
+    }
+
+    #[test]
+    fn config() {
+        let mut actions = ActionMap::default();
+        actions
+            .insert(
+                "jump",
+                [
+                    Binding::key(KeyCode::Space),
+                    Binding::mouse(MouseButton::Other(4)),
+                ],
+            )
+            .insert(
+                "save",
+                [Binding::chord([
+                    Button::from(ModifierKey::Control),
+                    KeyCode::KeyS.into(),
+                ])],
+            )
+            .insert("zoom", [Binding::axis(KeyCode::Minus, KeyCode::Equal)])
+            .insert("move", [Binding::wasd()])
+            .insert("look", [Binding::MouseMotion])
+            .insert(
+                "odd",
+                [Binding::key(KeyCode::Unidentified(NativeCode::Xkb(300)))],
+            )
+            .insert(" a = b # c\n", [Binding::key(KeyCode::KeyQ)]);
+
+        let config = actions.to_config().unwrap();
+        assert!(
+            config.contains("\"save\": [\n        Chord([Modifier(Control), Key(KeyS)]),\n    ],")
+        );
+
+        let mut loaded = actions.clone();
+        for name in actions.actions() {
+            loaded.rebind(name, []).unwrap();
+        }
+        loaded.load_config(&config).unwrap();
+
+        for name in actions.actions() {
+            assert_eq!(loaded.bindings(name), actions.bindings(name));
+        }
+
+        // Invalid configs change nothing.
+        let err = loaded
+            .load_config(r#"{ "jump": [Chord([Key(Enter)])], "zoom": [Axis(Key(Minus))] }"#)
+            .unwrap_err();
+        assert!(matches!(err, ActionError::Deserialize(_)));
+        assert_eq!(loaded.bindings("jump"), actions.bindings("jump"));
+
+        loaded
+            .load_config(r#"{ "jump": [Chord([Key(Enter)])], "removed": [MouseMotion] }"#)
+            .unwrap();
+        assert_eq!(
+            loaded.bindings("jump"),
+            Some(&[Binding::key(KeyCode::Enter)][..])
+        );
+    }
+}
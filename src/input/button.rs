@@ -0,0 +1,152 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use ahash::HashSet;
+
+use crate::ecs::resource::Resource;
+
+/// A resource holding which buttons of a kind, like [`KeyCode`](super::keyboard::KeyCode)s or
+/// [`MouseButton`](super::MouseButton)s, are held down.
+///
+/// The app updates it before firing the input events, and clears what was just pressed or
+/// released at the end of every frame.
+///
+/// Example:
+/// ```no_run
+/// use woody::app::GameState;
+/// use woody::ecs::world::World;
+/// use woody::event::UpdateEvent;
+/// use woody::input::button::ButtonInput;
+/// use woody::input::keyboard::KeyCode;
+///
+/// fn movement(world: &mut World, state: GameState, _: UpdateEvent) {
+///     let keys = world.resource::<ButtonInput<KeyCode>>().unwrap();
+///
+///     if keys.pressed(KeyCode::KeyW) {
+///         // Moves every frame while it's held down.
+///     }
+///     if keys.just_pressed(KeyCode::Space) {
+///         // Jumps once per press.
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ButtonInput<T: Copy + Eq + Hash> {
+    pressed: HashSet<T>,
+    just_pressed: HashSet<T>,
+    just_released: HashSet<T>,
+}
+
+impl<T: Copy + Eq + Hash> Default for ButtonInput<T> {
+    fn default() -> Self {
+        Self {
+            pressed: HashSet::default(),
+            just_pressed: HashSet::default(),
+            just_released: HashSet::default(),
+        }
+    }
+}
+
+impl<T: Copy + Eq + Hash + Send + Sync + 'static> Resource for ButtonInput<T> {}
+
+impl<T: Copy + Eq + Hash> ButtonInput<T> {
+    /// Marks a button as pressed, it's just pressed unless it was already held down.
+    pub fn press(&mut self, button: T) {
+        if self.pressed.insert(button) {
+            self.just_pressed.insert(button);
+        }
+    }
+
+    /// Marks a button as released, it's just released if it was held down.
+    pub fn release(&mut self, button: T) {
+        if self.pressed.remove(&button) {
+            self.just_released.insert(button);
+        }
+    }
+
+    /// Releases every button held down.
+    pub fn release_all(&mut self) {
+        self.just_released.extend(self.pressed.drain());
+    }
+
+    /// Checks if a button is held down.
+    pub fn pressed(&self, button: T) -> bool {
+        self.pressed.contains(&button)
+    }
+
+    /// Checks if a button was pressed during this frame.
+    pub fn just_pressed(&self, button: T) -> bool {
+        self.just_pressed.contains(&button)
+    }
+
+    /// Checks if a button was released during this frame.
+    pub fn just_released(&self, button: T) -> bool {
+        self.just_released.contains(&button)
+    }
+
+    /// Checks if any of the buttons is held down.
+    pub fn any_pressed(&self, buttons: impl IntoIterator<Item = T>) -> bool {
+        buttons.into_iter().any(|button| self.pressed(button))
+    }
+
+    /// Checks if any of the buttons was pressed during this frame.
+    pub fn any_just_pressed(&self, buttons: impl IntoIterator<Item = T>) -> bool {
+        buttons.into_iter().any(|button| self.just_pressed(button))
+    }
+
+    /// Returns the buttons held down.
+    pub fn get_pressed(&self) -> impl Iterator<Item = T> + '_ {
+        self.pressed.iter().copied()
+    }
+
+    /// Returns the buttons pressed during this frame.
+    pub fn get_just_pressed(&self) -> impl Iterator<Item = T> + '_ {
+        self.just_pressed.iter().copied()
+    }
+
+    /// Returns the buttons released during this frame.
+    pub fn get_just_released(&self) -> impl Iterator<Item = T> + '_ {
+        self.just_released.iter().copied()
+    }
+
+    /// Forgets what was just pressed or released, which the app does at the end of every frame.
+    pub fn clear(&mut self) {
+        self.just_pressed.clear();
+        self.just_released.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::input::keyboard::KeyCode;
+
+    #[test]
+    fn press_and_release() {
+        let mut keys = ButtonInput::<KeyCode>::default();
+
+        keys.press(KeyCode::KeyW);
+        assert!(keys.pressed(KeyCode::KeyW));
+        assert!(keys.just_pressed(KeyCode::KeyW));
+        assert!(keys.any_pressed([KeyCode::KeyA, KeyCode::KeyW]));
+
+        keys.clear();
+        // Repeated presses while held down aren't new presses.
+        keys.press(KeyCode::KeyW);
+        assert!(keys.pressed(KeyCode::KeyW));
+        assert!(!keys.just_pressed(KeyCode::KeyW));
+
+        keys.release(KeyCode::KeyW);
+        keys.release(KeyCode::KeyA);
+        assert!(!keys.pressed(KeyCode::KeyW));
+        assert!(keys.just_released(KeyCode::KeyW));
+        assert!(!keys.just_released(KeyCode::KeyA));
+
+        keys.press(KeyCode::KeyA);
+        keys.press(KeyCode::KeyD);
+        keys.clear();
+        keys.release_all();
+        assert_eq!(keys.get_pressed().count(), 0);
+        assert_eq!(keys.get_just_released().count(), 2);
+    }
+}
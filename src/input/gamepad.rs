@@ -0,0 +1,677 @@
+use std::fmt::Debug;
+
+use ahash::HashMap;
+use glam::Vec2;
+use smallvec::smallvec;
+use smallvec::SmallVec;
+
+use crate::app::GameState;
+use crate::ecs::resource::Resource;
+use crate::ecs::world::World;
+use crate::event::Event;
+use crate::systems::Systems;
+
+use super::button::ButtonInput;
+
+#[cfg(feature = "gilrs")]
+pub use self::gilrs::GilrsBackend;
+pub use self::mock::MockGamepadBackend;
+
+#[cfg(feature = "gilrs")]
+mod gilrs;
+mod mock;
+
+/// Identifies a gamepad as long as it stays connected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct GamepadId(pub usize);
+
+/// A gamepad button, named after its position on the pad.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadButton {
+    /// The bottom face button, like A on Xbox or Cross on PlayStation pads.
+    South,
+    /// The right face button, like B on Xbox or Circle on PlayStation pads.
+    East,
+    /// The top face button, like Y on Xbox or Triangle on PlayStation pads.
+    North,
+    /// The left face button, like X on Xbox or Square on PlayStation pads.
+    West,
+    C,
+    Z,
+    /// The left bumper.
+    LeftTrigger,
+    /// The left trigger, which is usually analog.
+    LeftTrigger2,
+    /// The right bumper.
+    RightTrigger,
+    /// The right trigger, which is usually analog.
+    RightTrigger2,
+    Select,
+    Start,
+    Mode,
+    LeftThumb,
+    RightThumb,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+}
+
+/// A gamepad axis, between `-1` and `1`, with positive `y` pointing up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GamepadAxis {
+    LeftStickX,
+    LeftStickY,
+    LeftZ,
+    RightStickX,
+    RightStickY,
+    RightZ,
+    DPadX,
+    DPadY,
+}
+
+impl GamepadAxis {
+    /// Returns the `x` and `y` axes of the stick this axis belongs to, if any.
+    fn stick(self) -> Option<(GamepadAxis, GamepadAxis)> {
+        match self {
+            GamepadAxis::LeftStickX | GamepadAxis::LeftStickY => {
+                Some((GamepadAxis::LeftStickX, GamepadAxis::LeftStickY))
+            }
+            GamepadAxis::RightStickX | GamepadAxis::RightStickY => {
+                Some((GamepadAxis::RightStickX, GamepadAxis::RightStickY))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// A raw input reported by a [`GamepadBackend`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum GamepadInput {
+    Connected {
+        id: GamepadId,
+        name: String,
+    },
+    Disconnected {
+        id: GamepadId,
+    },
+    /// A button moved, `value` is between `0` and `1` as some buttons are analog.
+    Button {
+        id: GamepadId,
+        button: GamepadButton,
+        value: f32,
+    },
+    Axis {
+        id: GamepadId,
+        axis: GamepadAxis,
+        value: f32,
+    },
+}
+
+/// Where the gamepad inputs come from, polled by the app at the start of every frame.
+///
+/// With the `gilrs` feature, the [`GilrsBackend`] is used unless another one is set with
+/// [`AppBuilder::gamepad_backend`](crate::app::AppBuilder::gamepad_backend), and headless apps
+/// have no backend unless one is set.
+pub trait GamepadBackend: Debug {
+    /// Pushes the inputs received since the last poll.
+    fn poll(&mut self, inputs: &mut Vec<GamepadInput>);
+}
+
+/// Fired when a gamepad is connected or disconnected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GamepadConnectionEvent {
+    pub id: GamepadId,
+    pub connected: bool,
+}
+
+impl Event for GamepadConnectionEvent {}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GamepadButtonState {
+    Pressed,
+    Released,
+}
+
+/// Fired when a gamepad button is pressed or released, see [`Gamepads::button_threshold`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GamepadButtonEvent {
+    pub id: GamepadId,
+    pub button: GamepadButton,
+    pub state: GamepadButtonState,
+    pub value: f32,
+}
+
+impl Event for GamepadButtonEvent {}
+
+/// Fired when a gamepad axis moves, once the deadzone is applied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GamepadAxisEvent {
+    pub id: GamepadId,
+    pub axis: GamepadAxis,
+    pub value: f32,
+}
+
+impl Event for GamepadAxisEvent {}
+
+/// The state of a connected gamepad.
+#[derive(Debug, Clone)]
+pub struct Gamepad {
+    name: String,
+    buttons: ButtonInput<GamepadButton>,
+    values: HashMap<GamepadButton, f32>,
+    /// The values of the axes with the deadzone applied.
+    axes: HashMap<GamepadAxis, f32>,
+    /// The values of the axes as reported by the backend.
+    raw_axes: HashMap<GamepadAxis, f32>,
+}
+
+impl Gamepad {
+    fn new(name: String) -> Self {
+        Self {
+            name,
+            buttons: ButtonInput::default(),
+            values: HashMap::default(),
+            axes: HashMap::default(),
+            raw_axes: HashMap::default(),
+        }
+    }
+
+    /// Returns the name reported by the backend.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the buttons of this gamepad, to check which ones are held down.
+    pub fn buttons(&self) -> &ButtonInput<GamepadButton> {
+        &self.buttons
+    }
+
+    /// Checks if a button is held down.
+    pub fn pressed(&self, button: GamepadButton) -> bool {
+        self.buttons.pressed(button)
+    }
+
+    /// Checks if a button was pressed during this frame.
+    pub fn just_pressed(&self, button: GamepadButton) -> bool {
+        self.buttons.just_pressed(button)
+    }
+
+    /// Checks if a button was released during this frame.
+    pub fn just_released(&self, button: GamepadButton) -> bool {
+        self.buttons.just_released(button)
+    }
+
+    /// Returns how far a button is pushed, between `0` and `1`.
+    pub fn button_value(&self, button: GamepadButton) -> f32 {
+        self.values.get(&button).copied().unwrap_or_default()
+    }
+
+    /// Returns the value of an axis with the deadzone applied.
+    pub fn axis(&self, axis: GamepadAxis) -> f32 {
+        self.axes.get(&axis).copied().unwrap_or_default()
+    }
+
+    /// Returns the position of the left stick.
+    pub fn left_stick(&self) -> Vec2 {
+        Vec2::new(
+            self.axis(GamepadAxis::LeftStickX),
+            self.axis(GamepadAxis::LeftStickY),
+        )
+    }
+
+    /// Returns the position of the right stick.
+    pub fn right_stick(&self) -> Vec2 {
+        Vec2::new(
+            self.axis(GamepadAxis::RightStickX),
+            self.axis(GamepadAxis::RightStickY),
+        )
+    }
+}
+
+/// The deadzone of an axis: values closer to `0` than `inner` read as `0`, values beyond `outer`
+/// read as `±1`, and the values in between are rescaled.
+///
+/// Note: the sticks apply it to their distance from the center rather than to each axis, so
+/// diagonals aren't snapped to the axes, see [`Deadzone::apply_radial`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Deadzone {
+    pub inner: f32,
+    pub outer: f32,
+}
+
+impl Deadzone {
+    /// Creates a new [`Deadzone`].
+    ///
+    /// Panics if `inner` is negative or isn't below `outer`.
+    pub fn new(inner: f32, outer: f32) -> Self {
+        assert!(
+            0.0 <= inner && inner < outer,
+            "The deadzone must have 0 <= inner < outer, got inner {inner} and outer {outer}"
+        );
+
+        Self { inner, outer }
+    }
+
+    /// Applies this deadzone to the raw position of a stick, keeping its direction.
+    pub fn apply_radial(&self, position: Vec2) -> Vec2 {
+        let distance = position.length();
+        if distance == 0.0 {
+            return Vec2::ZERO;
+        }
+
+        position / distance * self.apply(distance)
+    }
+
+    /// Applies this deadzone to a raw value.
+    pub fn apply(&self, value: f32) -> f32 {
+        let magnitude = value.abs();
+
+        if magnitude <= self.inner {
+            0.0
+        } else if magnitude >= self.outer {
+            value.signum()
+        } else {
+            value.signum() * (magnitude - self.inner) / (self.outer - self.inner)
+        }
+    }
+}
+
+impl Default for Deadzone {
+    fn default() -> Self {
+        Self {
+            inner: 0.1,
+            outer: 0.95,
+        }
+    }
+}
+
+/// An event to fire after handling a [`GamepadInput`].
+enum GamepadEvent {
+    Connection(GamepadConnectionEvent),
+    Button(GamepadButtonEvent),
+    Axis(GamepadAxisEvent),
+}
+
+/// A resource holding the connected gamepads and how their inputs are read.
+///
+/// Example:
+/// ```no_run
+/// use woody::app::GameState;
+/// use woody::ecs::world::World;
+/// use woody::event::UpdateEvent;
+/// use woody::input::gamepad::GamepadAxis;
+/// use woody::input::gamepad::GamepadButton;
+/// use woody::input::gamepad::GamepadConnectionEvent;
+/// use woody::input::gamepad::Gamepads;
+///
+/// fn connection(world: &mut World, _: GameState, event: GamepadConnectionEvent) {
+///     let gamepads = world.resource_mut::<Gamepads>().unwrap();
+///
+///     if event.connected {
+///         // Worn sticks drift further, this sets the deadzone of the whole left stick.
+///         gamepads.set_deadzone(GamepadAxis::LeftStickX, 0.2, 0.95);
+///     }
+/// }
+///
+/// fn play(world: &mut World, state: GameState, _: UpdateEvent) {
+///     let gamepads = world.resource::<Gamepads>().unwrap();
+///
+///     for (id, gamepad) in gamepads.iter() {
+///         let movement = gamepad.left_stick() * state.delta_time as f32;
+///         if gamepad.just_pressed(GamepadButton::South) {}
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Gamepads {
+    gamepads: HashMap<GamepadId, Gamepad>,
+    /// The deadzone of the axes without their own.
+    pub deadzone: Deadzone,
+    deadzones: HashMap<GamepadAxis, Deadzone>,
+    /// How far a button must be pushed to count as pressed.
+    pub button_threshold: f32,
+}
+
+impl Default for Gamepads {
+    fn default() -> Self {
+        Self {
+            gamepads: HashMap::default(),
+            deadzone: Deadzone::default(),
+            deadzones: HashMap::default(),
+            button_threshold: 0.5,
+        }
+    }
+}
+
+impl Resource for Gamepads {}
+
+impl Gamepads {
+    /// Returns a connected gamepad.
+    pub fn get(&self, id: GamepadId) -> Option<&Gamepad> {
+        self.gamepads.get(&id)
+    }
+
+    /// Returns the connected gamepads.
+    pub fn iter(&self) -> impl Iterator<Item = (GamepadId, &Gamepad)> {
+        self.gamepads.iter().map(|(id, gamepad)| (*id, gamepad))
+    }
+
+    /// Returns how many gamepads are connected.
+    pub fn len(&self) -> usize {
+        self.gamepads.len()
+    }
+
+    /// Checks if no gamepad is connected.
+    pub fn is_empty(&self) -> bool {
+        self.gamepads.is_empty()
+    }
+
+    /// Sets the deadzone of an axis, see [`Deadzone`].
+    ///
+    /// Note: it only applies to the next values of the axis. The deadzone of a stick is radial, so
+    /// setting it for one of its axes sets it for the other one too.
+    ///
+    /// Panics if `inner` is negative or isn't below `outer`.
+    pub fn set_deadzone(&mut self, axis: GamepadAxis, inner: f32, outer: f32) {
+        let deadzone = Deadzone::new(inner, outer);
+
+        match axis.stick() {
+            Some((x, y)) => {
+                self.deadzones.insert(x, deadzone);
+                self.deadzones.insert(y, deadzone);
+            }
+            None => {
+                self.deadzones.insert(axis, deadzone);
+            }
+        }
+    }
+
+    /// Returns the deadzone of an axis.
+    pub fn deadzone(&self, axis: GamepadAxis) -> Deadzone {
+        self.deadzones.get(&axis).copied().unwrap_or(self.deadzone)
+    }
+
+    /// Forgets the buttons just pressed or released, at the end of a frame.
+    pub(crate) fn clear(&mut self) {
+        for gamepad in self.gamepads.values_mut() {
+            gamepad.buttons.clear();
+        }
+    }
+
+    /// Updates the gamepads with an input, returning the events to fire for what changed.
+    fn handle(&mut self, input: GamepadInput) -> SmallVec<[GamepadEvent; 2]> {
+        match input {
+            GamepadInput::Connected { id, name } => {
+                if self.gamepads.contains_key(&id) {
+                    return SmallVec::new();
+                }
+                self.gamepads.insert(id, Gamepad::new(name));
+
+                smallvec![GamepadEvent::Connection(GamepadConnectionEvent {
+                    id,
+                    connected: true,
+                })]
+            }
+
+            GamepadInput::Disconnected { id } => {
+                if self.gamepads.remove(&id).is_none() {
+                    return SmallVec::new();
+                }
+
+                smallvec![GamepadEvent::Connection(GamepadConnectionEvent {
+                    id,
+                    connected: false,
+                })]
+            }
+
+            GamepadInput::Button { id, button, value } => {
+                let threshold = self.button_threshold;
+                let Some(gamepad) = self.gamepads.get_mut(&id) else {
+                    return SmallVec::new();
+                };
+                gamepad.values.insert(button, value);
+
+                let state = match (gamepad.pressed(button), value >= threshold) {
+                    (false, true) => {
+                        gamepad.buttons.press(button);
+                        GamepadButtonState::Pressed
+                    }
+                    (true, false) => {
+                        gamepad.buttons.release(button);
+                        GamepadButtonState::Released
+                    }
+                    _ => return SmallVec::new(),
+                };
+
+                smallvec![GamepadEvent::Button(GamepadButtonEvent {
+                    id,
+                    button,
+                    state,
+                    value,
+                })]
+            }
+
+            GamepadInput::Axis { id, axis, value } => {
+                let deadzone = self.deadzone(axis);
+                let Some(gamepad) = self.gamepads.get_mut(&id) else {
+                    return SmallVec::new();
+                };
+                gamepad.raw_axes.insert(axis, value);
+
+                // Both axes of a stick change when it moves, as its deadzone is radial.
+                let values: SmallVec<[(GamepadAxis, f32); 2]> = match axis.stick() {
+                    Some((x, y)) => {
+                        let position = deadzone.apply_radial(Vec2::new(
+                            gamepad.raw_axes.get(&x).copied().unwrap_or_default(),
+                            gamepad.raw_axes.get(&y).copied().unwrap_or_default(),
+                        ));
+
+                        smallvec![(x, position.x), (y, position.y)]
+                    }
+                    None => smallvec![(axis, deadzone.apply(value))],
+                };
+
+                values
+                    .into_iter()
+                    .filter(|&(axis, value)| {
+                        let previous = gamepad.axes.insert(axis, value).unwrap_or_default();
+                        previous != value
+                    })
+                    .map(|(axis, value)| GamepadEvent::Axis(GamepadAxisEvent { id, axis, value }))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// Polls the backend, updating the [`Gamepads`] and firing the events of the inputs.
+pub(crate) fn poll(
+    backend: &mut dyn GamepadBackend,
+    world: &mut World,
+    systems: &mut Systems,
+    state: GameState,
+) {
+    let mut inputs = Vec::new();
+    backend.poll(&mut inputs);
+
+    for input in inputs {
+        let Some(gamepads) = world.resource_mut::<Gamepads>() else {
+            continue;
+        };
+
+        for event in gamepads.handle(input) {
+            match event {
+                GamepadEvent::Connection(event) => systems.fire(world, state, event),
+                GamepadEvent::Button(event) => systems.fire(world, state, event),
+                GamepadEvent::Axis(event) => systems.fire(world, state, event),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::app::headless::HeadlessClock;
+    use crate::app::AppBuilder;
+    use crate::input::InputPlugin;
+
+    #[derive(Debug, Default)]
+    struct Fired(Vec<String>);
+
+    impl Resource for Fired {}
+
+    fn connection(world: &mut World, _: GameState, event: GamepadConnectionEvent) {
+        let text = format!("{event:?}");
+        world.resource_mut::<Fired>().unwrap().0.push(text);
+    }
+
+    fn button(world: &mut World, _: GameState, event: GamepadButtonEvent) {
+        let text = format!("{:?} {:?}", event.button, event.state);
+        world.resource_mut::<Fired>().unwrap().0.push(text);
+    }
+
+    #[test]
+    fn mock_backend() {
+        let backend = MockGamepadBackend::default();
+        let id = GamepadId(3);
+
+        let mut builder = AppBuilder::new();
+        builder.add_plugin(InputPlugin).init_resource::<Fired>();
+        builder.systems.subscribe(connection);
+        builder.systems.subscribe(button);
+        builder.gamepad_backend(backend.clone());
+        let mut app = builder.build_headless(HeadlessClock::default());
+
+        backend
+            .connect(id, "Pad")
+            .press(id, GamepadButton::South)
+            .move_axis(id, GamepadAxis::RightStickX, -1.0);
+        app.update();
+
+        let gamepads = app.world.resource::<Gamepads>().unwrap();
+        let gamepad = gamepads.get(id).unwrap();
+        assert!(gamepad.pressed(GamepadButton::South));
+        assert_eq!(gamepad.right_stick(), Vec2::NEG_X);
+
+        backend.release(id, GamepadButton::South).disconnect(id);
+        app.update();
+
+        assert!(app.world.resource::<Gamepads>().unwrap().is_empty());
+        assert_eq!(
+            app.world.resource::<Fired>().unwrap().0,
+            [
+                "GamepadConnectionEvent { id: GamepadId(3), connected: true }",
+                "South Pressed",
+                "South Released",
+                "GamepadConnectionEvent { id: GamepadId(3), connected: false }",
+            ]
+        );
+    }
+
+    #[test]
+    fn deadzone() {
+        let deadzone = Deadzone {
+            inner: 0.2,
+            outer: 0.8,
+        };
+
+        assert_eq!(deadzone.apply(0.1), 0.0);
+        assert_eq!(deadzone.apply(-0.2), 0.0);
+        assert_eq!(deadzone.apply(0.5), 0.5);
+        assert_eq!(deadzone.apply(-0.9), -1.0);
+
+        assert_eq!(deadzone.apply_radial(Vec2::new(0.1, 0.1)), Vec2::ZERO);
+        assert_eq!(deadzone.apply_radial(Vec2::new(0.0, -0.9)), Vec2::NEG_Y);
+        assert!(deadzone
+            .apply_radial(Vec2::new(0.3, 0.4))
+            .abs_diff_eq(Vec2::new(0.3, 0.4), 1e-6));
+    }
+
+    #[test]
+    #[should_panic(expected = "0 <= inner < outer")]
+    fn invalid_deadzone() {
+        Gamepads::default().set_deadzone(GamepadAxis::LeftZ, 0.5, 0.5);
+    }
+
+    #[test]
+    fn gamepads() {
+        let id = GamepadId(0);
+        let mut gamepads = Gamepads::default();
+
+        // Inputs of unknown gamepads are ignored.
+        assert!(gamepads
+            .handle(GamepadInput::Axis {
+                id,
+                axis: GamepadAxis::LeftStickX,
+                value: 1.0,
+            })
+            .is_empty());
+
+        let connected = GamepadInput::Connected {
+            id,
+            name: "Pad".to_string(),
+        };
+        assert_eq!(gamepads.handle(connected.clone()).len(), 1);
+        assert!(gamepads.handle(connected).is_empty());
+        assert_eq!(gamepads.get(id).unwrap().name(), "Pad");
+
+        let trigger = |value| GamepadInput::Button {
+            id,
+            button: GamepadButton::RightTrigger2,
+            value,
+        };
+        assert!(gamepads.handle(trigger(0.3)).is_empty());
+        assert!(matches!(
+            gamepads.handle(trigger(0.6)).as_slice(),
+            [GamepadEvent::Button(GamepadButtonEvent {
+                state: GamepadButtonState::Pressed,
+                ..
+            })]
+        ));
+        assert!(gamepads.handle(trigger(0.9)).is_empty());
+
+        let gamepad = gamepads.get(id).unwrap();
+        assert!(gamepad.just_pressed(GamepadButton::RightTrigger2));
+        assert_eq!(gamepad.button_value(GamepadButton::RightTrigger2), 0.9);
+
+        gamepads.set_deadzone(GamepadAxis::LeftStickY, 0.5, 1.0);
+        assert_eq!(
+            gamepads.deadzone(GamepadAxis::LeftStickX),
+            Deadzone::new(0.5, 1.0)
+        );
+        let stick = |axis, value| GamepadInput::Axis { id, axis, value };
+        // Moves inside the deadzone don't fire anything.
+        assert!(gamepads
+            .handle(stick(GamepadAxis::LeftStickY, 0.4))
+            .is_empty());
+        assert_eq!(
+            gamepads.handle(stick(GamepadAxis::LeftStickY, 0.75)).len(),
+            1
+        );
+        assert_eq!(gamepads.get(id).unwrap().left_stick(), Vec2::new(0.0, 0.5));
+
+        // The deadzone of a stick is radial, so a diagonal leaves it before each of its axes does,
+        // and both axes change.
+        gamepads.set_deadzone(GamepadAxis::RightStickX, 0.2, 1.0);
+        assert!(gamepads
+            .handle(stick(GamepadAxis::RightStickX, 0.15))
+            .is_empty());
+        assert_eq!(
+            gamepads.handle(stick(GamepadAxis::RightStickY, 0.15)).len(),
+            2
+        );
+        let right_stick = gamepads.get(id).unwrap().right_stick();
+        assert!(right_stick.x > 0.0 && right_stick.x == right_stick.y);
+
+        gamepads.clear();
+        assert!(!gamepads
+            .get(id)
+            .unwrap()
+            .just_pressed(GamepadButton::RightTrigger2));
+
+        assert_eq!(gamepads.handle(GamepadInput::Disconnected { id }).len(), 1);
+        assert!(gamepads.is_empty());
+    }
+}
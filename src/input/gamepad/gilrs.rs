@@ -0,0 +1,116 @@
+use gilrs::Axis;
+use gilrs::Button;
+use gilrs::EventType;
+use gilrs::Gilrs;
+
+use super::GamepadAxis;
+use super::GamepadBackend;
+use super::GamepadButton;
+use super::GamepadId;
+use super::GamepadInput;
+
+/// A [`GamepadBackend`] reading the gamepads through [gilrs](https://docs.rs/gilrs).
+///
+/// Note: it's only available with the `gilrs` feature.
+#[derive(Debug)]
+pub struct GilrsBackend {
+    gilrs: Gilrs,
+    /// The gamepads already connected when the backend was created.
+    connected: Vec<GamepadInput>,
+}
+
+impl GilrsBackend {
+    pub fn new() -> Result<Self, Box<gilrs::Error>> {
+        let gilrs = Gilrs::new().map_err(Box::new)?;
+        let connected = gilrs
+            .gamepads()
+            .map(|(id, gamepad)| GamepadInput::Connected {
+                id: GamepadId(id.into()),
+                name: gamepad.name().to_string(),
+            })
+            .collect();
+
+        Ok(Self { gilrs, connected })
+    }
+}
+
+impl GamepadBackend for GilrsBackend {
+    fn poll(&mut self, inputs: &mut Vec<GamepadInput>) {
+        inputs.append(&mut self.connected);
+
+        while let Some(gilrs::Event { id, event, .. }) = self.gilrs.next_event() {
+            let input = match event {
+                EventType::Connected => GamepadInput::Connected {
+                    id: GamepadId(id.into()),
+                    name: self.gilrs.gamepad(id).name().to_string(),
+                },
+                EventType::Disconnected => GamepadInput::Disconnected {
+                    id: GamepadId(id.into()),
+                },
+                // The pressed and released events come along with the changed ones.
+                EventType::ButtonChanged(button, value, _) => match convert_button(button) {
+                    Some(button) => GamepadInput::Button {
+                        id: GamepadId(id.into()),
+                        button,
+                        value,
+                    },
+                    None => continue,
+                },
+                EventType::AxisChanged(axis, value, _) => match convert_axis(axis) {
+                    Some(axis) => GamepadInput::Axis {
+                        id: GamepadId(id.into()),
+                        axis,
+                        value,
+                    },
+                    None => continue,
+                },
+                _ => continue,
+            };
+
+            inputs.push(input);
+        }
+    }
+}
+
+fn convert_button(button: Button) -> Option<GamepadButton> {
+    let button = match button {
+        Button::South => GamepadButton::South,
+        Button::East => GamepadButton::East,
+        Button::North => GamepadButton::North,
+        Button::West => GamepadButton::West,
+        Button::C => GamepadButton::C,
+        Button::Z => GamepadButton::Z,
+        Button::LeftTrigger => GamepadButton::LeftTrigger,
+        Button::LeftTrigger2 => GamepadButton::LeftTrigger2,
+        Button::RightTrigger => GamepadButton::RightTrigger,
+        Button::RightTrigger2 => GamepadButton::RightTrigger2,
+        Button::Select => GamepadButton::Select,
+        Button::Start => GamepadButton::Start,
+        Button::Mode => GamepadButton::Mode,
+        Button::LeftThumb => GamepadButton::LeftThumb,
+        Button::RightThumb => GamepadButton::RightThumb,
+        Button::DPadUp => GamepadButton::DPadUp,
+        Button::DPadDown => GamepadButton::DPadDown,
+        Button::DPadLeft => GamepadButton::DPadLeft,
+        Button::DPadRight => GamepadButton::DPadRight,
+        Button::Unknown => return None,
+    };
+
+    Some(button)
+}
+
+fn convert_axis(axis: Axis) -> Option<GamepadAxis> {
+    let axis = match axis {
+        Axis::LeftStickX => GamepadAxis::LeftStickX,
+        Axis::LeftStickY => GamepadAxis::LeftStickY,
+        Axis::LeftZ => GamepadAxis::LeftZ,
+        Axis::RightStickX => GamepadAxis::RightStickX,
+        Axis::RightStickY => GamepadAxis::RightStickY,
+        Axis::RightZ => GamepadAxis::RightZ,
+        Axis::DPadX => GamepadAxis::DPadX,
+        Axis::DPadY => GamepadAxis::DPadY,
+        Axis::Unknown => return None,
+    };
+
+    Some(axis)
+}
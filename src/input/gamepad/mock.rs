@@ -0,0 +1,92 @@
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use super::GamepadAxis;
+use super::GamepadBackend;
+use super::GamepadButton;
+use super::GamepadId;
+use super::GamepadInput;
+
+/// A [`GamepadBackend`] reporting scripted inputs, e.g. for tests.
+///
+/// Clones share the same script, so a clone can keep scripting the inputs once the backend was
+/// given to the app. The inputs scripted between two frames are all reported on the next one.
+///
+/// Example:
+/// ```
+/// use woody::app::headless::HeadlessClock;
+/// use woody::app::App;
+/// use woody::input::gamepad::GamepadButton;
+/// use woody::input::gamepad::GamepadId;
+/// use woody::input::gamepad::Gamepads;
+/// use woody::input::gamepad::MockGamepadBackend;
+/// use woody::input::InputPlugin;
+///
+/// let backend = MockGamepadBackend::default();
+/// let pad = GamepadId(0);
+///
+/// let mut builder = App::builder();
+/// builder.add_plugin(InputPlugin).gamepad_backend(backend.clone());
+/// let mut app = builder.build_headless(HeadlessClock::default());
+///
+/// backend.connect(pad, "Pad").press(pad, GamepadButton::South);
+/// app.update();
+///
+/// let gamepads = app.world.resource::<Gamepads>().unwrap();
+/// // The button was just pressed during that frame, and is still held.
+/// assert!(gamepads.get(pad).unwrap().pressed(GamepadButton::South));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MockGamepadBackend {
+    inputs: Arc<Mutex<Vec<GamepadInput>>>,
+}
+
+impl MockGamepadBackend {
+    /// Scripts an input for the next frame.
+    pub fn push(&self, input: GamepadInput) -> &Self {
+        self.inputs.lock().unwrap().push(input);
+        self
+    }
+
+    /// Scripts the connection of a gamepad.
+    pub fn connect(&self, id: GamepadId, name: &str) -> &Self {
+        self.push(GamepadInput::Connected {
+            id,
+            name: name.to_string(),
+        })
+    }
+
+    /// Scripts the disconnection of a gamepad.
+    pub fn disconnect(&self, id: GamepadId) -> &Self {
+        self.push(GamepadInput::Disconnected { id })
+    }
+
+    /// Scripts a button pushed all the way.
+    pub fn press(&self, id: GamepadId, button: GamepadButton) -> &Self {
+        self.push(GamepadInput::Button {
+            id,
+            button,
+            value: 1.0,
+        })
+    }
+
+    /// Scripts a button let go.
+    pub fn release(&self, id: GamepadId, button: GamepadButton) -> &Self {
+        self.push(GamepadInput::Button {
+            id,
+            button,
+            value: 0.0,
+        })
+    }
+
+    /// Scripts an axis moving to `value`.
+    pub fn move_axis(&self, id: GamepadId, axis: GamepadAxis, value: f32) -> &Self {
+        self.push(GamepadInput::Axis { id, axis, value })
+    }
+}
+
+impl GamepadBackend for MockGamepadBackend {
+    fn poll(&mut self, inputs: &mut Vec<GamepadInput>) {
+        inputs.append(&mut self.inputs.lock().unwrap());
+    }
+}
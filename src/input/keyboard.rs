@@ -1,21 +1,50 @@
+use serde::Deserialize;
+use serde::Serialize;
+pub use winit::keyboard::NamedKey;
+
+use crate::ecs::world::World;
 use crate::event::Event;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use super::button::ButtonInput;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct KeyboardEvent {
     pub state: KeyboardState,
+    /// The physical key, which doesn't depend on the keyboard layout, e.g. for movement keys.
     pub keycode: KeyCode,
+    /// The key as translated by the keyboard layout, e.g. for shortcuts.
+    pub logical_key: LogicalKey,
+    /// The modifiers held down when the key was pressed or released.
+    pub modifiers: Modifiers,
+    /// Whether it's a press repeated by holding the key down.
+    pub repeat: bool,
 }
 
 impl KeyboardEvent {
-    pub(crate) fn new(state: impl Into<KeyboardState>, key: impl Into<KeyCode>) -> Self {
+    pub(crate) fn new(event: &winit::event::KeyEvent, modifiers: Modifiers) -> Self {
         Self {
-            state: state.into(),
-            keycode: key.into(),
+            state: event.state.into(),
+            keycode: event.physical_key.into(),
+            logical_key: (&event.logical_key).into(),
+            modifiers,
+            repeat: event.repeat,
+        }
+    }
+
+    /// Updates the [`ButtonInput`] of the keys with this event.
+    pub(crate) fn track(&self, world: &mut World) {
+        let Some(keys) = world.resource_mut::<ButtonInput<KeyCode>>() else {
+            return;
+        };
+
+        match self.state {
+            KeyboardState::Pressed => keys.press(self.keycode),
+            KeyboardState::Released => keys.release(self.keycode),
         }
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum KeyboardState {
     Pressed,
     Released,
@@ -30,11 +59,112 @@ impl From<winit::event::ElementState> for KeyboardState {
     }
 }
 
+/// The modifier keys held down.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Modifiers {
+    pub shift: bool,
+    pub control: bool,
+    pub alt: bool,
+    /// The Windows, Command or Meta key.
+    pub super_key: bool,
+}
+
+impl Modifiers {
+    /// Checks if no modifier is held down.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+impl From<winit::keyboard::ModifiersState> for Modifiers {
+    fn from(value: winit::keyboard::ModifiersState) -> Self {
+        Self {
+            shift: value.shift_key(),
+            control: value.control_key(),
+            alt: value.alt_key(),
+            super_key: value.super_key(),
+        }
+    }
+}
+
+/// The key of a keyboard input as translated by the keyboard layout.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LogicalKey {
+    /// A key without a character, like Enter or Shift.
+    Named(NamedKey),
+    /// A key producing text, taking the modifiers into account.
+    ///
+    /// Note: it's usually a single character, but some layouts produce many of them at once.
+    Character(String),
+    /// A dead key, which combines with the next key, along with the character it produces on its
+    /// own.
+    Dead(Option<char>),
+    /// A key unknown to the layout, identified by its platform specific code.
+    ///
+    /// Note: unlike for [`KeyCode::Unidentified`], the code is the one of the translated key, e.g.
+    /// an XKB "keysym" or a Windows "virtual-key code".
+    Unidentified(NativeCode),
+}
+
+impl From<&winit::keyboard::Key> for LogicalKey {
+    fn from(value: &winit::keyboard::Key) -> Self {
+        match value {
+            winit::keyboard::Key::Named(key) => LogicalKey::Named(*key),
+            winit::keyboard::Key::Character(text) => LogicalKey::Character(text.to_string()),
+            winit::keyboard::Key::Dead(character) => LogicalKey::Dead(*character),
+            winit::keyboard::Key::Unidentified(code) => LogicalKey::Unidentified(code.into()),
+        }
+    }
+}
+
+/// A platform specific code of a key unknown to [`KeyCode`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NativeCode {
+    Unidentified,
+    /// An Android "scancode".
+    Android(u32),
+    /// A macOS "scancode".
+    MacOS(u16),
+    /// A Windows "scancode".
+    Windows(u16),
+    /// An XKB "keycode".
+    Xkb(u32),
+}
+
+impl From<winit::keyboard::NativeKeyCode> for NativeCode {
+    fn from(value: winit::keyboard::NativeKeyCode) -> Self {
+        match value {
+            winit::keyboard::NativeKeyCode::Unidentified => NativeCode::Unidentified,
+            winit::keyboard::NativeKeyCode::Android(code) => NativeCode::Android(code),
+            winit::keyboard::NativeKeyCode::MacOS(code) => NativeCode::MacOS(code),
+            winit::keyboard::NativeKeyCode::Windows(code) => NativeCode::Windows(code),
+            winit::keyboard::NativeKeyCode::Xkb(code) => NativeCode::Xkb(code),
+        }
+    }
+}
+
+impl From<&winit::keyboard::NativeKey> for NativeCode {
+    fn from(value: &winit::keyboard::NativeKey) -> Self {
+        match value {
+            winit::keyboard::NativeKey::Android(code) => NativeCode::Android(*code),
+            winit::keyboard::NativeKey::MacOS(code) => NativeCode::MacOS(*code),
+            winit::keyboard::NativeKey::Windows(code) => NativeCode::Windows(*code),
+            winit::keyboard::NativeKey::Xkb(code) => NativeCode::Xkb(*code),
+            // The web isn't supported.
+            winit::keyboard::NativeKey::Unidentified | winit::keyboard::NativeKey::Web(_) => {
+                NativeCode::Unidentified
+            }
+        }
+    }
+}
+
 /// The key code of a keyboard input.
 ///
 /// Note: This is just a carbon copy from `winit`'s `VirtualKeyCode`.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum KeyCode {
+    /// A key unknown to the engine, identified by its platform specific code.
+    Unidentified(NativeCode),
     Backquote,
     Backslash,
     BracketLeft,
@@ -428,9 +558,64 @@ impl From<winit::keyboard::KeyCode> for KeyCode {
             winit::keyboard::KeyCode::F33 => KeyCode::F33,
             winit::keyboard::KeyCode::F34 => KeyCode::F34,
             winit::keyboard::KeyCode::F35 => KeyCode::F35,
-            _ => todo!(),
+            _ => KeyCode::Unidentified(NativeCode::Unidentified),
+        }
+    }
+}
+
+impl From<winit::keyboard::PhysicalKey> for KeyCode {
+    fn from(value: winit::keyboard::PhysicalKey) -> Self {
+        match value {
+            winit::keyboard::PhysicalKey::Code(code) => code.into(),
+            winit::keyboard::PhysicalKey::Unidentified(native) => {
+                KeyCode::Unidentified(native.into())
+            }
         }
     }
 }
 
 impl Event for KeyboardEvent {}
+
+#[cfg(test)]
+mod test {
+    use winit::keyboard::Key;
+    use winit::keyboard::NativeKey;
+    use winit::keyboard::NativeKeyCode;
+    use winit::keyboard::PhysicalKey;
+
+    use super::*;
+
+    #[test]
+    fn conversions() {
+        let code = PhysicalKey::Code(winit::keyboard::KeyCode::KeyQ);
+        assert_eq!(KeyCode::from(code), KeyCode::KeyQ);
+
+        let native = PhysicalKey::Unidentified(NativeKeyCode::Xkb(300));
+        assert_eq!(
+            KeyCode::from(native),
+            KeyCode::Unidentified(NativeCode::Xkb(300))
+        );
+
+        let character = Key::Character("é".into());
+        assert_eq!(
+            LogicalKey::from(&character),
+            LogicalKey::Character("é".to_owned())
+        );
+
+        // Every character is kept for keys producing many of them.
+        let characters = Key::Character("ﾃﾞ".into());
+        assert_eq!(
+            LogicalKey::from(&characters),
+            LogicalKey::Character("ﾃﾞ".to_owned())
+        );
+
+        let unknown = Key::Unidentified(NativeKey::Xkb(0x1008ff13));
+        assert_eq!(
+            LogicalKey::from(&unknown),
+            LogicalKey::Unidentified(NativeCode::Xkb(0x1008ff13))
+        );
+
+        let named = Key::Named(NamedKey::Enter);
+        assert_eq!(LogicalKey::from(&named), LogicalKey::Named(NamedKey::Enter));
+    }
+}
@@ -0,0 +1,535 @@
+use std::collections::VecDeque;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use log::warn;
+use ron::ser::PrettyConfig;
+use serde::Deserialize;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::app::GameState;
+use crate::ecs::resource::Resource;
+use crate::ecs::world::World;
+use crate::event::FocusChangedEvent;
+use crate::input;
+use crate::systems::Systems;
+
+use super::keyboard::KeyboardEvent;
+use super::text::ImeEvent;
+use super::text::TextInputEvent;
+use super::CursorEvent;
+use super::MouseEvent;
+use super::MouseMotionEvent;
+use super::MouseWheelEvent;
+
+/// An input event which can be recorded and replayed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum RecordedInput {
+    Keyboard(KeyboardEvent),
+    Text(TextInputEvent),
+    Ime(ImeEvent),
+    Mouse(MouseEvent),
+    MouseWheel(MouseWheelEvent),
+    Cursor(CursorEvent),
+    MouseMotion(MouseMotionEvent),
+    /// The window gained or lost the focus, which releases every button when it's lost.
+    Focus(FocusChangedEvent),
+}
+
+impl RecordedInput {
+    /// Records this input if an [`InputRecorder`] is inserted, then updates the input resources
+    /// and fires its event. `frame` is the app frame whose update sees the input.
+    pub(crate) fn fire(
+        self,
+        world: &mut World,
+        systems: &mut Systems,
+        state: GameState,
+        frame: u64,
+    ) {
+        if let Some(recorder) = world.resource_mut::<InputRecorder>() {
+            recorder.record(frame, self.clone());
+        }
+
+        match self {
+            RecordedInput::Keyboard(event) => {
+                event.track(world);
+                systems.fire(world, state, event);
+            }
+            RecordedInput::Text(event) => systems.fire(world, state, event),
+            RecordedInput::Ime(event) => systems.fire(world, state, event),
+            RecordedInput::Mouse(event) => {
+                event.track(world);
+                systems.fire(world, state, event);
+            }
+            RecordedInput::MouseWheel(event) => {
+                event.track(world);
+                systems.fire(world, state, event);
+            }
+            RecordedInput::Cursor(event) => systems.fire(world, state, event),
+            RecordedInput::MouseMotion(event) => {
+                event.track(world);
+                systems.fire(world, state, event);
+            }
+            RecordedInput::Focus(event) => {
+                if !event.focused {
+                    input::release_all(world);
+                }
+                systems.fire(world, state, event);
+            }
+        }
+    }
+}
+
+/// An input along with when it was received.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordedEvent {
+    /// The frame whose update sees the input, counting from the start of the recording.
+    pub frame: u64,
+    /// The time on the app clock at the end of the previous frame.
+    ///
+    /// Note: inputs are only timed to the frame, the ones received during the same frame share
+    /// the same time.
+    pub time: Duration,
+    pub input: RecordedInput,
+}
+
+/// Inputs recorded by an [`InputRecorder`], which can be saved to a file and replayed with
+/// [`AppBuilder::replay`](crate::app::AppBuilder::replay).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct InputRecording {
+    /// The app frame the recording started on, which the replay starts on too.
+    pub start_frame: u64,
+    /// The number of frames recorded.
+    pub frames: u64,
+    /// The average duration of the recorded frames, which the replay runs at.
+    ///
+    /// Note: it's the exact duration of every frame if the app was recorded with a fixed clock,
+    /// which is needed for the replay to reproduce the session identically.
+    pub frame_time: Duration,
+    /// Whether every recorded frame lasted the same duration, as with a fixed clock.
+    #[serde(default)]
+    pub fixed_clock: bool,
+    pub events: Vec<RecordedEvent>,
+}
+
+impl InputRecording {
+    /// Writes this recording to a file, in the RON format.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), ReplayError> {
+        let text = ron::ser::to_string_pretty(self, PrettyConfig::default())?;
+        fs::write(path, text)?;
+
+        Ok(())
+    }
+
+    /// Reads a recording from a file written by [`InputRecording::save`].
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ReplayError> {
+        let text = fs::read_to_string(path)?;
+
+        Ok(ron::from_str(&text)?)
+    }
+
+    /// Returns the duration of the frames of a replay, or `None` to keep the app clock if no frame
+    /// was recorded.
+    pub(crate) fn replay_step(&self) -> Option<Duration> {
+        if self.frame_time.is_zero() {
+            return None;
+        }
+
+        if !self.fixed_clock {
+            warn!(
+                "The recording wasn't made on a fixed clock, replaying it at its average frame \
+                 time may not reproduce the session"
+            );
+        }
+
+        Some(self.frame_time)
+    }
+}
+
+/// A resource recording the [`RecordedInput`]s while it's inserted, e.g. to attach the session to
+/// a bug report.
+///
+/// The recording starts on the frame the recorder is inserted, so the replay fires the inputs on
+/// the same app frames even if the recorder was inserted late.
+///
+/// Example:
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use woody::app::App;
+/// use woody::app::GameState;
+/// use woody::ecs::world::World;
+/// use woody::event::CreateEvent;
+/// use woody::event::ShutdownEvent;
+/// use woody::input::replay::InputRecorder;
+///
+/// fn record(world: &mut World, _: GameState, _: CreateEvent) {
+///     world.insert_resource(InputRecorder::default());
+/// }
+///
+/// fn save(world: &mut World, _: GameState, _: ShutdownEvent) {
+///     let recorder = world.resource::<InputRecorder>().unwrap();
+///     recorder.recording().save("session.ron").unwrap();
+/// }
+///
+/// let mut builder = App::builder();
+/// // Replays only reproduce the session identically with a fixed clock.
+/// builder.fixed_clock(Duration::from_secs(1) / 60);
+/// builder.systems.subscribe(record);
+/// builder.systems.subscribe(save);
+/// builder.run().unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct InputRecorder {
+    /// The app frame the recording started on, known once the recorder sees its first frame.
+    start_frame: Option<u64>,
+    frame: u64,
+    elapsed: Duration,
+    /// The duration of the first recorded frame.
+    step: Option<Duration>,
+    /// Whether a recorded frame lasted another duration than the first one.
+    variable: bool,
+    events: Vec<RecordedEvent>,
+}
+
+impl Resource for InputRecorder {}
+
+impl InputRecorder {
+    /// Returns the number of frames recorded so far.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Returns what was recorded so far.
+    pub fn recording(&self) -> InputRecording {
+        let frame_time = match u32::try_from(self.frame) {
+            Ok(0) | Err(_) => Duration::ZERO,
+            Ok(frames) => self.elapsed / frames,
+        };
+
+        InputRecording {
+            start_frame: self.start_frame.unwrap_or_default(),
+            frames: self.frame,
+            frame_time,
+            fixed_clock: !self.variable,
+            events: self.events.clone(),
+        }
+    }
+
+    fn record(&mut self, frame: u64, input: RecordedInput) {
+        let start_frame = *self.start_frame.get_or_insert(frame);
+
+        self.events.push(RecordedEvent {
+            frame: frame - start_frame,
+            time: self.elapsed,
+            input,
+        });
+    }
+}
+
+/// Counts the app frame `frame` lasting `delta` in the [`InputRecorder`], at the end of the frame.
+pub(crate) fn end_frame(world: &mut World, frame: u64, delta: Duration) {
+    if let Some(recorder) = world.resource_mut::<InputRecorder>() {
+        let start_frame = *recorder.start_frame.get_or_insert(frame);
+
+        recorder.frame = frame + 1 - start_frame;
+        recorder.elapsed += delta;
+
+        let step = *recorder.step.get_or_insert(delta);
+        recorder.variable |= delta != step;
+    }
+}
+
+/// Fires the inputs of a recording on the frames they were recorded on.
+#[derive(Debug)]
+pub(crate) struct InputReplay {
+    start_frame: u64,
+    events: VecDeque<RecordedEvent>,
+}
+
+impl InputReplay {
+    pub fn new(recording: InputRecording) -> Self {
+        Self {
+            start_frame: recording.start_frame,
+            events: recording.events.into(),
+        }
+    }
+
+    /// Checks if every input was fired.
+    pub fn is_finished(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// Fires the inputs of the app frame `frame`, at the start of the frame.
+    pub fn update(
+        &mut self,
+        world: &mut World,
+        systems: &mut Systems,
+        state: GameState,
+        frame: u64,
+    ) {
+        while let Some(event) = self.events.front() {
+            if self.start_frame + event.frame > frame {
+                break;
+            }
+
+            let event = self.events.pop_front().unwrap();
+            event.input.fire(world, systems, state, frame);
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum ReplayError {
+    #[error("Could not read or write the recording: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Could not write the recording: {0}")]
+    Serialize(#[from] ron::Error),
+
+    #[error("Invalid recording: {0}")]
+    Deserialize(#[from] ron::error::SpannedError),
+}
+
+#[cfg(test)]
+mod test {
+    use glam::Vec2;
+
+    use super::*;
+    use crate::app::headless::HeadlessClock;
+    use crate::app::AppBuilder;
+    use crate::event::CreateEvent;
+    use crate::event::UpdateEvent;
+    use crate::input::button::ButtonInput;
+    use crate::input::gamepad::GamepadId;
+    use crate::input::gamepad::Gamepads;
+    use crate::input::gamepad::MockGamepadBackend;
+    use crate::input::keyboard::KeyCode;
+    use crate::input::keyboard::KeyboardState;
+    use crate::input::keyboard::LogicalKey;
+    use crate::input::keyboard::Modifiers;
+    use crate::input::keyboard::NamedKey;
+    use crate::input::InputPlugin;
+    use crate::input::MouseButton;
+    use crate::input::MouseState;
+    use crate::input::ScrollUnit;
+    use crate::time::Time;
+    use crate::time::TimePlugin;
+
+    /// Where the player went, moving right while `D` is held.
+    #[derive(Debug, Default, Clone, Copy, PartialEq)]
+    struct Player(f64);
+
+    impl Resource for Player {}
+
+    fn setup(world: &mut World, _: GameState, _: CreateEvent) {
+        world.init_resource::<Player>();
+        world.insert_resource(InputRecorder::default());
+    }
+
+    fn movement(world: &mut World, state: GameState, _: UpdateEvent) {
+        let keys = world.resource::<ButtonInput<KeyCode>>().unwrap();
+
+        if keys.pressed(KeyCode::KeyD) {
+            world.resource_mut::<Player>().unwrap().0 += state.delta_time;
+        }
+    }
+
+    fn builder() -> AppBuilder {
+        let mut builder = AppBuilder::new();
+        builder.add_plugin(InputPlugin);
+        builder.systems.subscribe(setup);
+        builder.systems.subscribe(movement);
+        builder
+    }
+
+    fn key(state: KeyboardState) -> RecordedInput {
+        RecordedInput::Keyboard(KeyboardEvent {
+            state,
+            keycode: KeyCode::KeyD,
+            logical_key: LogicalKey::Character("d".to_owned()),
+            modifiers: Modifiers::default(),
+            repeat: false,
+        })
+    }
+
+    #[test]
+    fn replay() {
+        let frame_time = Duration::from_millis(20);
+        let events = [
+            (2, key(KeyboardState::Pressed)),
+            (2, RecordedInput::Text(TextInputEvent::new("d").unwrap())),
+            (2, RecordedInput::Cursor(CursorEvent { x: 4.0, y: 2.0 })),
+            (
+                5,
+                RecordedInput::MouseMotion(MouseMotionEvent {
+                    delta: Vec2::new(1.0, -1.0),
+                }),
+            ),
+            (
+                5,
+                RecordedInput::Mouse(MouseEvent {
+                    state: MouseState::Pressed,
+                    button: MouseButton::Other(8),
+                }),
+            ),
+            (
+                5,
+                RecordedInput::MouseWheel(MouseWheelEvent {
+                    unit: ScrollUnit::Line,
+                    delta: Vec2::Y,
+                    raw: false,
+                }),
+            ),
+            (
+                5,
+                RecordedInput::Focus(FocusChangedEvent { focused: false }),
+            ),
+            (7, key(KeyboardState::Released)),
+        ];
+        let recording = InputRecording {
+            start_frame: 0,
+            frames: 10,
+            frame_time,
+            fixed_clock: true,
+            events: events
+                .into_iter()
+                .map(|(frame, input)| RecordedEvent {
+                    frame,
+                    time: frame_time * frame as u32,
+                    input,
+                })
+                .collect(),
+        };
+
+        let path = std::env::temp_dir().join(format!("woody-replay-{}.ron", std::process::id()));
+        recording.save(&path).unwrap();
+        let loaded = InputRecording::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, recording);
+
+        let backend = MockGamepadBackend::default();
+        let pad = GamepadId(0);
+        backend.connect(pad, "Pad");
+
+        let mut builder = builder();
+        builder.replay(loaded).gamepad_backend(backend);
+        let mut app = builder.build_headless(HeadlessClock::Manual);
+
+        // The live inputs wait until the replay is over.
+        app.run_for(5);
+        let gamepads = app.world.resource::<Gamepads>().unwrap();
+        assert!(gamepads.get(pad).is_none());
+
+        app.run_for(recording.frames - 5);
+        let gamepads = app.world.resource::<Gamepads>().unwrap();
+        assert!(gamepads.get(pad).is_some());
+
+        // The key was held during frames 2 to 4, until the window lost the focus.
+        let expected = (frame_time * 3).as_secs_f64();
+        assert!((app.world.resource::<Player>().unwrap().0 - expected).abs() < 1e-9);
+        assert_eq!(app.elapsed(), frame_time * 10);
+
+        // Recording the replay gives back the same session.
+        let recorder = app.world.resource::<InputRecorder>().unwrap();
+        assert_eq!(recorder.recording(), recording);
+    }
+
+    /// Starts recording on frame 5.
+    fn record_late(world: &mut World, _: GameState, _: UpdateEvent) {
+        if world.resource::<Time>().unwrap().frame() == 5 {
+            world.insert_resource(InputRecorder::default());
+        }
+    }
+
+    #[test]
+    fn late_recording() {
+        let frame_time = Duration::from_millis(20);
+        let recording = InputRecording {
+            start_frame: 0,
+            frames: 12,
+            frame_time,
+            fixed_clock: true,
+            events: [
+                (7, key(KeyboardState::Pressed)),
+                (9, key(KeyboardState::Released)),
+            ]
+            .into_iter()
+            .map(|(frame, input)| RecordedEvent {
+                frame,
+                time: frame_time * frame as u32,
+                input,
+            })
+            .collect(),
+        };
+
+        let mut late = AppBuilder::new();
+        late.add_plugin(InputPlugin)
+            .add_plugin(TimePlugin)
+            .init_resource::<Player>()
+            .replay(recording.clone());
+        late.systems.subscribe(movement);
+        late.systems.subscribe(record_late);
+        let mut app = late.build_headless(HeadlessClock::Manual);
+        app.run_for(recording.frames);
+
+        let late = app.world.resource::<InputRecorder>().unwrap().recording();
+        assert_eq!(late.start_frame, 5);
+        assert_eq!(late.frames, 7);
+        assert_eq!(late.events[0].frame, 2);
+
+        // Replaying the late recording fires the inputs on the same app frames.
+        let mut builder = builder();
+        builder.replay(late);
+        let mut replayed = builder.build_headless(HeadlessClock::Manual);
+        replayed.run_for(recording.frames);
+
+        assert_eq!(
+            replayed.world.resource::<Player>(),
+            app.world.resource::<Player>()
+        );
+        let recorder = replayed.world.resource::<InputRecorder>().unwrap();
+        assert_eq!(recorder.recording(), recording);
+    }
+
+    #[test]
+    fn clocks() {
+        // An empty recording keeps the clock of the app.
+        let mut empty = builder();
+        empty.replay(InputRecording::default());
+        let mut app = empty.build_headless(HeadlessClock::fixed_rate(4.0));
+        app.run_for(2);
+        assert_eq!(app.elapsed(), Duration::from_millis(500));
+
+        let recording = app.world.resource::<InputRecorder>().unwrap().recording();
+        assert_eq!(recording.frame_time, Duration::from_millis(250));
+        assert!(recording.fixed_clock);
+
+        let mut app = builder().build_headless(HeadlessClock::Manual);
+        app.advance(Duration::from_millis(10));
+        app.run_for(2);
+
+        let recording = app.world.resource::<InputRecorder>().unwrap().recording();
+        assert_eq!(recording.frame_time, Duration::from_millis(5));
+        assert!(!recording.fixed_clock);
+    }
+
+    #[test]
+    fn named_keys() {
+        let input = RecordedInput::Keyboard(KeyboardEvent {
+            state: KeyboardState::Pressed,
+            keycode: KeyCode::Enter,
+            logical_key: LogicalKey::Named(NamedKey::Enter),
+            modifiers: Modifiers {
+                shift: true,
+                ..Default::default()
+            },
+            repeat: true,
+        });
+
+        let text = ron::to_string(&input).unwrap();
+        assert_eq!(ron::from_str::<RecordedInput>(&text).unwrap(), input);
+    }
+}
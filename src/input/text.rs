@@ -0,0 +1,132 @@
+use glam::Vec2;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::ecs::resource::Resource;
+use crate::event::Event;
+
+/// Fired with the text typed by a key press, taking the keyboard layout and modifiers into
+/// account, e.g. for chat boxes.
+///
+/// Note: control characters, like the ones typed by Enter, Backspace, Tab or Escape, are left
+/// out, the [`KeyboardEvent`](super::keyboard::KeyboardEvent) tells about those keys. The text
+/// composed with an input method editor is fired as an [`ImeEvent::Commit`] instead.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextInputEvent {
+    pub text: String,
+}
+
+impl TextInputEvent {
+    /// Creates the event of the text typed by a key, unless it's only control characters.
+    pub(crate) fn new(text: &str) -> Option<Self> {
+        let text: String = text.chars().filter(|c| !c.is_control()).collect();
+
+        (!text.is_empty()).then_some(Self { text })
+    }
+}
+
+impl Event for TextInputEvent {}
+
+/// Fired by the input method editor of the window, used to compose text of languages like
+/// Chinese or Japanese.
+///
+/// Note: it's only fired while [`Ime::enabled`] is set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ImeEvent {
+    /// The editor was enabled, so [`Ime::cursor_position`] should be kept up to date.
+    Enabled,
+    /// The text being composed changed, which should be shown at the cursor replacing the
+    /// previous one.
+    ///
+    /// The cursor is the byte range of the text to highlight, or `None` to hide it. An empty text
+    /// means the composition was cleared.
+    Preedit {
+        text: String,
+        cursor: Option<(usize, usize)>,
+    },
+    /// The text was composed and should be inserted at the cursor.
+    Commit(String),
+    /// The editor was disabled, so the text being composed should be cleared.
+    Disabled,
+}
+
+impl From<winit::event::Ime> for ImeEvent {
+    fn from(value: winit::event::Ime) -> Self {
+        match value {
+            winit::event::Ime::Enabled => ImeEvent::Enabled,
+            winit::event::Ime::Preedit(text, cursor) => ImeEvent::Preedit { text, cursor },
+            winit::event::Ime::Commit(text) => ImeEvent::Commit(text),
+            winit::event::Ime::Disabled => ImeEvent::Disabled,
+        }
+    }
+}
+
+impl Event for ImeEvent {}
+
+/// A resource controlling the input method editor of the window, applied at the end of the frame.
+///
+/// Example:
+/// ```no_run
+/// use glam::vec2;
+/// use woody::app::GameState;
+/// use woody::ecs::world::World;
+/// use woody::event::UpdateEvent;
+/// use woody::input::text::Ime;
+///
+/// fn focus_chat(world: &mut World, _: GameState, _: UpdateEvent) {
+///     let ime = world.resource_mut::<Ime>().unwrap();
+///
+///     ime.enabled = true;
+///     // Shows the candidates box right below the chat box.
+///     ime.cursor_position = vec2(20.0, 680.0);
+///     ime.cursor_size = vec2(400.0, 24.0);
+/// }
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct Ime {
+    /// Whether the editor is allowed, which disables the [`TextInputEvent`]s of the keys used to
+    /// compose text on some platforms.
+    pub enabled: bool,
+    /// The physical position of the text cursor in the window, where the editor places its
+    /// candidates box.
+    pub cursor_position: Vec2,
+    /// The physical size of the text cursor, or of the whole text field.
+    pub cursor_size: Vec2,
+}
+
+impl Resource for Ime {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn text_input() {
+        assert_eq!(TextInputEvent::new("é").unwrap().text, "é");
+        assert_eq!(TextInputEvent::new("a\u{7f}b").unwrap().text, "ab");
+
+        // Enter, Backspace, Tab and Escape.
+        for text in ["\r", "\u{8}", "\t", "\u{1b}"] {
+            assert_eq!(TextInputEvent::new(text), None);
+        }
+    }
+
+    #[test]
+    fn ime() {
+        let preedit = winit::event::Ime::Preedit("にほ".to_string(), Some((0, 6)));
+        assert_eq!(
+            ImeEvent::from(preedit),
+            ImeEvent::Preedit {
+                text: "にほ".to_string(),
+                cursor: Some((0, 6)),
+            }
+        );
+
+        let commit = winit::event::Ime::Commit("日本".to_string());
+        assert_eq!(ImeEvent::from(commit), ImeEvent::Commit("日本".to_string()));
+        let enabled = ImeEvent::from(winit::event::Ime::Enabled);
+        assert_eq!(enabled, ImeEvent::Enabled);
+        let disabled = ImeEvent::from(winit::event::Ime::Disabled);
+        assert_eq!(disabled, ImeEvent::Disabled);
+    }
+}
@@ -1,9 +1,15 @@
 pub mod app;
+pub mod condition;
+pub mod diagnostics;
 pub mod ecs;
 pub mod event;
 pub mod graphics;
 pub mod input;
+pub mod plugin;
+pub mod state;
 mod systems;
+pub mod time;
+pub mod window;
 
 #[cfg(feature = "dhat-heap")]
 #[global_allocator]
@@ -4,18 +4,23 @@ use glam::Quat;
 use glam::Vec3;
 use rayon::prelude::ParallelIterator;
 use woody::app::App;
+use woody::app::AppBuilder;
 use woody::app::GameState;
 use woody::ecs::world::World;
 use woody::event::CreateEvent;
 use woody::event::UpdateEvent;
 use woody::graphics::camera::Camera;
+use woody::input::action::ActionMap;
+use woody::input::action::Binding;
 use woody::input::keyboard::KeyCode;
 use woody::input::keyboard::KeyboardEvent;
-use woody::input::keyboard::KeyboardState;
 use woody::input::MouseButton;
 use woody::input::MouseEvent;
 use woody::input::MouseMotionEvent;
 use woody::input::MouseState;
+use woody::plugin::DefaultPlugins;
+use woody::plugin::Plugin;
+use woody::window::Cursor;
 
 #[derive(Debug, Component)]
 pub struct Position(f64, f64, f64);
@@ -26,16 +31,30 @@ pub struct Velocity(u8, u8);
 #[derive(Debug, Component)]
 pub struct Health(u8);
 
-fn main() {
-    let (mut app, event_loop) = App::new().unwrap();
-
-    app.systems.subscribe(setup);
-    // app.systems.subscribe(positions);
-    app.systems.subscribe(handle_player_movement);
-    app.systems.subscribe(handle_camera_movement);
-    // app.systems.subscribe(handle_shot);
+struct PlayerPlugin;
+
+impl Plugin for PlayerPlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        let mut actions = ActionMap::default();
+        actions.insert("move", [Binding::wasd(), Binding::arrows()]);
+        app.insert_resource(actions);
+
+        app.systems.subscribe(setup);
+        // app.systems.subscribe(positions);
+        app.systems.subscribe(handle_player_movement);
+        app.systems.subscribe(handle_camera_movement);
+        app.systems.subscribe(capture_cursor);
+        app.systems.subscribe(release_cursor);
+        // app.systems.subscribe(handle_shot);
+    }
+}
 
-    app.run(event_loop).unwrap();
+fn main() {
+    App::builder()
+        .add_plugins(DefaultPlugins)
+        .add_plugin(PlayerPlugin)
+        .run()
+        .unwrap();
 }
 
 fn setup(world: &mut World, _: GameState, _: CreateEvent) {
@@ -45,6 +64,20 @@ fn setup(world: &mut World, _: GameState, _: CreateEvent) {
     });
 
     world.spawn((Position(0.0, 0.0, -30.0), Velocity(5, 0)));
+
+    world.resource_mut::<Cursor>().unwrap().lock();
+}
+
+fn capture_cursor(world: &mut World, _: GameState, event: MouseEvent) {
+    if event.state == MouseState::Pressed {
+        world.resource_mut::<Cursor>().unwrap().lock();
+    }
+}
+
+fn release_cursor(world: &mut World, _: GameState, event: KeyboardEvent) {
+    if event.keycode == KeyCode::Escape {
+        world.resource_mut::<Cursor>().unwrap().release();
+    }
 }
 
 fn _positions(world: &mut World, state: GameState, _: UpdateEvent) {
@@ -56,26 +89,15 @@ fn _positions(world: &mut World, state: GameState, _: UpdateEvent) {
     });
 }
 
-fn handle_player_movement(world: &mut World, state: GameState, event: KeyboardEvent) {
+fn handle_player_movement(world: &mut World, state: GameState, _: UpdateEvent) {
     static SPEED: f32 = 25.0;
 
+    let movement = world.resource::<ActionMap>().unwrap().axis_2d("move");
+
     let mut query = world.query::<&mut Camera>();
 
     query.par_iter().for_each(|mut cam| {
-        let mut velocity = Vec3::ZERO;
-
-        if event.state == KeyboardState::Released {
-            return;
-        }
-
-        velocity += match event.keycode {
-            KeyCode::KeyW => cam.forward(),
-            KeyCode::KeyS => cam.backward(),
-            KeyCode::KeyA => cam.left(),
-            KeyCode::KeyD => cam.right(),
-
-            _ => vec3(0.0, 0.0, 0.0),
-        };
+        let velocity = cam.forward() * movement.y + cam.right() * movement.x;
 
         if !velocity.abs_diff_eq(Vec3::ZERO, 0.0002) {
             cam.position += velocity * SPEED * state.delta_time as f32;
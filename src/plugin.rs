@@ -0,0 +1,248 @@
+use std::any::type_name;
+use std::any::TypeId;
+
+use crate::app::AppBuilder;
+use crate::diagnostics::DiagnosticsPlugin;
+use crate::input::InputPlugin;
+use crate::time::TimePlugin;
+
+/// A bundle of systems, resources, states and events added to an app at once.
+///
+/// Example:
+/// ```no_run
+/// use woody::app::App;
+/// use woody::app::AppBuilder;
+/// use woody::app::GameState;
+/// use woody::ecs::world::World;
+/// use woody::event::UpdateEvent;
+/// use woody::plugin::DefaultPlugins;
+/// use woody::plugin::Plugin;
+///
+/// struct PhysicsPlugin;
+///
+/// impl Plugin for PhysicsPlugin {
+///     fn build(&self, app: &mut AppBuilder) {
+///         app.systems.subscribe(step);
+///     }
+/// }
+///
+/// fn step(world: &mut World, state: GameState, _: UpdateEvent) {}
+///
+/// App::builder()
+///     .add_plugins(DefaultPlugins)
+///     .add_plugin(PhysicsPlugin)
+///     .run()
+///     .unwrap();
+/// ```
+pub trait Plugin: 'static {
+    /// Adds the systems, resources and states of this plugin to the app.
+    fn build(&self, app: &mut AppBuilder);
+
+    /// The name of this plugin, used to skip the ones added twice.
+    fn name(&self) -> &str {
+        type_name::<Self>()
+    }
+
+    /// Whether this plugin is skipped when another one with the same name was already added.
+    fn is_unique(&self) -> bool {
+        true
+    }
+}
+
+/// A set of plugins added together, whose plugins can be reordered or disabled before being
+/// added.
+///
+/// Example:
+/// ```no_run
+/// use woody::app::App;
+/// use woody::diagnostics::DiagnosticsPlugin;
+/// use woody::plugin::DefaultPlugins;
+/// use woody::plugin::PluginGroup;
+///
+/// App::builder()
+///     .add_plugins(DefaultPlugins.build().disable::<DiagnosticsPlugin>())
+///     .run()
+///     .unwrap();
+/// ```
+pub trait PluginGroup {
+    /// Returns the plugins of this group in the order they're added.
+    fn build(self) -> PluginGroupBuilder;
+}
+
+/// A plugin of a [`PluginGroupBuilder`].
+struct PluginEntry {
+    type_id: TypeId,
+    plugin: Box<dyn Plugin>,
+    enabled: bool,
+}
+
+/// The ordered plugins of a [`PluginGroup`].
+#[derive(Default)]
+pub struct PluginGroupBuilder {
+    plugins: Vec<PluginEntry>,
+}
+
+impl PluginGroupBuilder {
+    /// Appends a plugin, replacing the plugin of the same type if there's one already.
+    #[allow(clippy::should_implement_trait)]
+    pub fn add<P: Plugin>(mut self, plugin: P) -> Self {
+        match self.position::<P>() {
+            Some(index) => self.plugins[index].plugin = Box::new(plugin),
+            None => self.plugins.push(PluginEntry::new(plugin)),
+        }
+
+        self
+    }
+
+    /// Inserts a plugin right before the plugin `Target`, or appends it if there's no `Target`.
+    pub fn add_before<Target: Plugin, P: Plugin>(self, plugin: P) -> Self {
+        self.insert_at::<Target, P>(plugin, 0)
+    }
+
+    /// Inserts a plugin right after the plugin `Target`, or appends it if there's no `Target`.
+    pub fn add_after<Target: Plugin, P: Plugin>(self, plugin: P) -> Self {
+        self.insert_at::<Target, P>(plugin, 1)
+    }
+
+    /// Keeps the plugin `P` from being added.
+    pub fn disable<P: Plugin>(mut self) -> Self {
+        if let Some(index) = self.position::<P>() {
+            self.plugins[index].enabled = false;
+        }
+
+        self
+    }
+
+    /// Adds the plugin `P` again after it was disabled.
+    pub fn enable<P: Plugin>(mut self) -> Self {
+        if let Some(index) = self.position::<P>() {
+            self.plugins[index].enabled = true;
+        }
+
+        self
+    }
+
+    /// Checks if the plugin `P` is in this group and enabled.
+    pub fn is_enabled<P: Plugin>(&self) -> bool {
+        self.position::<P>()
+            .is_some_and(|index| self.plugins[index].enabled)
+    }
+
+    /// Returns the enabled plugins in order.
+    pub(crate) fn finish(self) -> impl Iterator<Item = Box<dyn Plugin>> {
+        self.plugins
+            .into_iter()
+            .filter(|entry| entry.enabled)
+            .map(|entry| entry.plugin)
+    }
+
+    fn position<P: Plugin>(&self) -> Option<usize> {
+        self.plugins
+            .iter()
+            .position(|entry| entry.type_id == TypeId::of::<P>())
+    }
+
+    /// Moves or inserts a plugin at `offset` from the plugin `Target`.
+    fn insert_at<Target: Plugin, P: Plugin>(mut self, plugin: P, offset: usize) -> Self {
+        if let Some(index) = self.position::<P>() {
+            self.plugins.remove(index);
+        }
+
+        let entry = PluginEntry::new(plugin);
+        match self.position::<Target>() {
+            Some(index) => self.plugins.insert(index + offset, entry),
+            None => self.plugins.push(entry),
+        }
+
+        self
+    }
+}
+
+impl PluginGroup for PluginGroupBuilder {
+    fn build(self) -> PluginGroupBuilder {
+        self
+    }
+}
+
+impl PluginEntry {
+    fn new<P: Plugin>(plugin: P) -> Self {
+        Self {
+            type_id: TypeId::of::<P>(),
+            plugin: Box::new(plugin),
+            enabled: true,
+        }
+    }
+}
+
+/// The plugins of the engine features: [`TimePlugin`], [`InputPlugin`] and
+/// [`DiagnosticsPlugin`].
+pub struct DefaultPlugins;
+
+impl PluginGroup for DefaultPlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::default()
+            .add(TimePlugin)
+            .add(InputPlugin)
+            .add(DiagnosticsPlugin::default())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ecs::resource::Resource;
+
+    #[derive(Debug, Default)]
+    struct Order(Vec<&'static str>);
+
+    impl Resource for Order {}
+
+    fn push(app: &mut AppBuilder, name: &'static str) {
+        app.init_resource::<Order>();
+        app.world.resource_mut::<Order>().unwrap().0.push(name);
+    }
+
+    struct A;
+    struct B;
+    struct C;
+
+    impl Plugin for A {
+        fn build(&self, app: &mut AppBuilder) {
+            push(app, "a");
+        }
+    }
+
+    impl Plugin for B {
+        fn build(&self, app: &mut AppBuilder) {
+            push(app, "b");
+        }
+    }
+
+    impl Plugin for C {
+        fn build(&self, app: &mut AppBuilder) {
+            push(app, "c");
+        }
+
+        fn is_unique(&self) -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn groups() {
+        let group = PluginGroupBuilder::default()
+            .add(A)
+            .add(B)
+            .add_before::<A, C>(C)
+            .disable::<B>();
+        assert!(!group.is_enabled::<B>());
+
+        let mut app = AppBuilder::new();
+        app.add_plugins(group).add_plugin(A).add_plugin(C);
+
+        // A is unique so it's only added once, unlike C.
+        assert_eq!(app.world.resource::<Order>().unwrap().0, ["c", "a", "c"]);
+        assert!(app.is_plugin_added(A.name()));
+        assert!(!app.is_plugin_added(B.name()));
+    }
+}
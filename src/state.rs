@@ -0,0 +1,295 @@
+use std::any::Any;
+use std::any::TypeId;
+use std::fmt::Debug;
+use std::hash::Hash;
+
+use ahash::HashMap;
+
+use crate::app::GameState;
+use crate::condition::Condition;
+use crate::ecs::resource::Resource;
+use crate::ecs::world::World;
+
+/// The states of a state machine, like the screens of a game.
+///
+/// Example:
+/// ```no_run
+/// use woody::app::App;
+/// use woody::app::GameState;
+/// use woody::ecs::world::World;
+/// use woody::event::UpdateEvent;
+/// use woody::state::in_state;
+/// use woody::state::NextState;
+/// use woody::state::States;
+///
+/// #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+/// enum Screen {
+///     Menu,
+///     Playing,
+/// }
+///
+/// impl States for Screen {}
+///
+/// fn spawn_level(world: &mut World, _: GameState) {}
+///
+/// fn move_player(world: &mut World, _: GameState, _: UpdateEvent) {}
+///
+/// fn start(world: &mut World, _: GameState, _: UpdateEvent) {
+///     world.resource_mut::<NextState<Screen>>().unwrap().set(Screen::Playing);
+/// }
+///
+/// let (mut app, event_loop) = App::new().unwrap();
+///
+/// app.init_state(Screen::Menu);
+/// app.systems.on_enter(Screen::Playing, spawn_level);
+/// app.systems.subscribe(start).run_if(in_state(Screen::Menu));
+/// app.systems.subscribe(move_player).run_if(in_state(Screen::Playing));
+///
+/// app.run(event_loop).unwrap();
+/// ```
+pub trait States: Debug + Copy + Eq + Hash + Send + Sync + 'static {}
+
+/// A resource holding the current state `S`.
+///
+/// Note: it can't be changed directly, set the [`NextState`] instead so the exit and enter handlers
+/// run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct State<S: States>(S);
+
+impl<S: States> State<S> {
+    /// Returns the current state.
+    pub fn get(&self) -> S {
+        self.0
+    }
+}
+
+impl<S: States> Resource for State<S> {}
+
+/// A resource holding the state `S` to transition to, which is applied before the next update.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NextState<S: States>(Option<S>);
+
+impl<S: States> NextState<S> {
+    /// Queues a transition to `state`, replacing the one already queued.
+    pub fn set(&mut self, state: S) {
+        self.0 = Some(state);
+    }
+
+    /// Returns the queued state.
+    pub fn pending(&self) -> Option<S> {
+        self.0
+    }
+}
+
+impl<S: States> Resource for NextState<S> {}
+
+/// Runs the system only while the current state `S` is `state`.
+pub fn in_state<S: States>(state: S) -> impl Condition {
+    move |world: &World, _: GameState| {
+        world
+            .resource::<State<S>>()
+            .is_some_and(|current| current.get() == state)
+    }
+}
+
+/// A handler invoked when entering or exiting a state.
+pub type StateHandler = fn(&mut World, GameState);
+
+/// The handlers of the states `S`.
+struct StateSchedules<S: States> {
+    /// The initial state, which is entered the first time transitions are applied.
+    initial: Option<S>,
+    on_enter: Vec<(S, StateHandler)>,
+    on_exit: Vec<(S, StateHandler)>,
+}
+
+impl<S: States> Default for StateSchedules<S> {
+    fn default() -> Self {
+        Self {
+            initial: None,
+            on_enter: Vec::new(),
+            on_exit: Vec::new(),
+        }
+    }
+}
+
+impl<S: States> StateSchedules<S> {
+    fn handlers(schedule: &[(S, StateHandler)], state: S) -> Vec<StateHandler> {
+        schedule
+            .iter()
+            .filter(|(s, _)| *s == state)
+            .map(|(_, handler)| *handler)
+            .collect()
+    }
+}
+
+/// The state machines of an app, along with their enter and exit handlers.
+#[derive(Debug, Default)]
+pub(crate) struct StateMachines {
+    schedules: HashMap<TypeId, Box<dyn Any>>,
+    /// Applies the queued transition of each state type.
+    transitions: Vec<fn(&mut StateMachines, &mut World, GameState)>,
+}
+
+impl StateMachines {
+    /// Adds the state machine `S` starting at `initial`.
+    pub fn init<S: States>(&mut self, world: &mut World, initial: S) {
+        world.insert_resource(State(initial));
+        world.insert_resource(NextState::<S>(None));
+
+        if !self.schedules.contains_key(&TypeId::of::<S>()) {
+            self.transitions.push(Self::transition::<S>);
+        }
+        self.schedules_mut::<S>().initial = Some(initial);
+    }
+
+    pub fn on_enter<S: States>(&mut self, state: S, handler: StateHandler) {
+        self.schedules_mut::<S>().on_enter.push((state, handler));
+    }
+
+    pub fn on_exit<S: States>(&mut self, state: S, handler: StateHandler) {
+        self.schedules_mut::<S>().on_exit.push((state, handler));
+    }
+
+    /// Applies the queued transition of every state machine.
+    ///
+    /// Note: transitions queued by the enter and exit handlers are applied the next time.
+    pub fn apply(&mut self, world: &mut World, state: GameState) {
+        for transition in self.transitions.clone() {
+            transition(self, world, state);
+        }
+    }
+
+    fn schedules_mut<S: States>(&mut self) -> &mut StateSchedules<S> {
+        self.schedules
+            .entry(TypeId::of::<S>())
+            .or_insert_with(|| Box::<StateSchedules<S>>::default())
+            .downcast_mut::<StateSchedules<S>>()
+            .unwrap()
+    }
+
+    fn transition<S: States>(&mut self, world: &mut World, state: GameState) {
+        let schedules = self.schedules_mut::<S>();
+
+        // The initial state is entered without exiting anything.
+        if let Some(initial) = schedules.initial.take() {
+            for handler in StateSchedules::handlers(&schedules.on_enter, initial) {
+                handler(world, state);
+            }
+        }
+
+        let Some(next) = world
+            .resource_mut::<NextState<S>>()
+            .and_then(|next| next.0.take())
+        else {
+            return;
+        };
+        let Some(current) = world.resource::<State<S>>().map(State::get) else {
+            return;
+        };
+
+        if current == next {
+            return;
+        }
+
+        let schedules = self.schedules_mut::<S>();
+        let on_exit = StateSchedules::handlers(&schedules.on_exit, current);
+        let on_enter = StateSchedules::handlers(&schedules.on_enter, next);
+
+        for handler in on_exit {
+            handler(world, state);
+        }
+
+        world.insert_resource(State(next));
+
+        for handler in on_enter {
+            handler(world, state);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::AtomicUsize;
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+    use crate::event::UpdateEvent;
+    use crate::systems::Systems;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    enum Screen {
+        Menu,
+        Playing,
+    }
+
+    impl States for Screen {}
+
+    #[test]
+    fn transitions() {
+        static ENTERED_MENU: AtomicUsize = AtomicUsize::new(0);
+        static ENTERED_PLAYING: AtomicUsize = AtomicUsize::new(0);
+        static EXITED_MENU: AtomicUsize = AtomicUsize::new(0);
+        static PLAYED: AtomicUsize = AtomicUsize::new(0);
+
+        fn enter_menu(_: &mut World, _: GameState) {
+            ENTERED_MENU.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn enter_playing(_: &mut World, _: GameState) {
+            ENTERED_PLAYING.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn exit_menu(_: &mut World, _: GameState) {
+            EXITED_MENU.fetch_add(1, Ordering::Relaxed);
+        }
+
+        fn play(_: &mut World, _: GameState, _: UpdateEvent) {
+            PLAYED.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let mut world = World::new();
+        let mut systems = Systems::default();
+        let state = GameState {
+            delta_time: 0.0,
+            last_time: 0.0,
+        };
+
+        systems.init_state(&mut world, Screen::Menu);
+        systems.on_enter(Screen::Menu, enter_menu);
+        systems.on_enter(Screen::Playing, enter_playing);
+        systems.on_exit(Screen::Menu, exit_menu);
+        systems.subscribe(play).run_if(in_state(Screen::Playing));
+
+        systems.apply_state_transitions(&mut world, state);
+        systems.fire(&mut world, state, UpdateEvent);
+        assert_eq!(ENTERED_MENU.load(Ordering::Relaxed), 1);
+        assert_eq!(PLAYED.load(Ordering::Relaxed), 0);
+
+        world
+            .resource_mut::<NextState<Screen>>()
+            .unwrap()
+            .set(Screen::Playing);
+        // Nothing changes until the transition is applied.
+        assert!(in_state(Screen::Menu).evaluate(&world, state));
+
+        systems.apply_state_transitions(&mut world, state);
+        systems.fire(&mut world, state, UpdateEvent);
+        assert_eq!(EXITED_MENU.load(Ordering::Relaxed), 1);
+        assert_eq!(ENTERED_PLAYING.load(Ordering::Relaxed), 1);
+        assert_eq!(PLAYED.load(Ordering::Relaxed), 1);
+        assert_eq!(
+            world.resource::<State<Screen>>().unwrap().get(),
+            Screen::Playing
+        );
+
+        // Transitioning to the current state does nothing.
+        world
+            .resource_mut::<NextState<Screen>>()
+            .unwrap()
+            .set(Screen::Playing);
+        systems.apply_state_transitions(&mut world, state);
+        assert_eq!(ENTERED_PLAYING.load(Ordering::Relaxed), 1);
+        assert_eq!(ENTERED_MENU.load(Ordering::Relaxed), 1);
+    }
+}
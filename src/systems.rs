@@ -2,20 +2,50 @@ use crate::app::GameState;
 use crate::ecs::world::World;
 use crate::event::Event;
 use crate::event::InnerSystem;
+use crate::event::SystemConfig;
+use crate::state::StateHandler;
+use crate::state::StateMachines;
+use crate::state::States;
 
 // TODO: instead of handling all states on flight, create an event buffering that will store all
 // "fired" events and always handle them at once.
 #[derive(Debug, Default)]
 pub struct Systems {
     inner: InnerSystem,
+    states: StateMachines,
 }
 
 impl Systems {
-    pub fn subscribe<E: Event + 'static>(&mut self, handler: fn(&mut World, GameState, E)) {
-        self.inner.subscribe(handler);
+    pub fn subscribe<E: Event + 'static>(
+        &mut self,
+        handler: fn(&mut World, GameState, E),
+    ) -> SystemConfig<'_, E> {
+        self.inner.subscribe(handler)
     }
 
     pub fn fire<E: Event + 'static>(&mut self, world: &mut World, state: GameState, event: E) {
         self.inner.handle(world, state, event);
     }
+
+    /// Adds the state machine `S` starting at `initial`, inserting its
+    /// [`State`](crate::state::State) and [`NextState`](crate::state::NextState) resources.
+    pub fn init_state<S: States>(&mut self, world: &mut World, initial: S) {
+        self.states.init(world, initial);
+    }
+
+    /// Registers a handler invoked when entering `state`.
+    pub fn on_enter<S: States>(&mut self, state: S, handler: StateHandler) {
+        self.states.on_enter(state, handler);
+    }
+
+    /// Registers a handler invoked when exiting `state`.
+    pub fn on_exit<S: States>(&mut self, state: S, handler: StateHandler) {
+        self.states.on_exit(state, handler);
+    }
+
+    /// Applies the state transitions queued in the [`NextState`](crate::state::NextState)
+    /// resources.
+    pub fn apply_state_transitions(&mut self, world: &mut World, state: GameState) {
+        self.states.apply(world, state);
+    }
 }
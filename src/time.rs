@@ -0,0 +1,76 @@
+use std::time::Duration;
+
+use crate::app::AppBuilder;
+use crate::ecs::resource::Resource;
+use crate::ecs::world::World;
+use crate::plugin::Plugin;
+
+/// A resource holding the time on the app clock, updated at the start of every frame.
+///
+/// Example:
+/// ```
+/// use woody::app::headless::HeadlessClock;
+/// use woody::app::App;
+/// use woody::time::Time;
+/// use woody::time::TimePlugin;
+///
+/// let mut builder = App::builder();
+/// builder.add_plugin(TimePlugin);
+///
+/// let mut app = builder.build_headless(HeadlessClock::fixed_rate(4.0));
+/// app.run_for(3);
+///
+/// let time = app.world.resource::<Time>().unwrap();
+/// assert_eq!(time.frame(), 2);
+/// assert_eq!(time.delta().as_millis(), 250);
+/// assert_eq!(time.elapsed().as_millis(), 750);
+/// ```
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Time {
+    frame: u64,
+    delta: Duration,
+    elapsed: Duration,
+}
+
+impl Resource for Time {}
+
+impl Time {
+    /// Returns the number of the current frame, starting at `0`.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Returns the duration of the current frame.
+    pub fn delta(&self) -> Duration {
+        self.delta
+    }
+
+    /// Returns the duration of the current frame in seconds.
+    pub fn delta_secs(&self) -> f32 {
+        self.delta.as_secs_f32()
+    }
+
+    /// Returns the time elapsed since the app started, up to the end of the current frame.
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+}
+
+/// Updates the [`Time`] at the start of frame `frame`, which lasts `delta`.
+pub(crate) fn update(world: &mut World, frame: u64, delta: Duration) {
+    if let Some(time) = world.resource_mut::<Time>() {
+        time.frame = frame;
+        time.delta = delta;
+        time.elapsed += delta;
+    }
+}
+
+/// Inserts the [`Time`] resource.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TimePlugin;
+
+impl Plugin for TimePlugin {
+    fn build(&self, app: &mut AppBuilder) {
+        app.init_resource::<Time>();
+    }
+}
@@ -0,0 +1,263 @@
+use glam::IVec2;
+use glam::UVec2;
+use glam::Vec2;
+use log::warn;
+use winit::dpi::LogicalSize;
+use winit::dpi::PhysicalPosition;
+use winit::error::OsError;
+use winit::event_loop::EventLoop;
+use winit::window::CursorGrabMode;
+use winit::window::Fullscreen;
+use winit::window::Window;
+use winit::window::WindowBuilder;
+
+pub use winit::window::CursorIcon;
+
+use crate::ecs::resource::Resource;
+
+/// A resource configuring the window created by the app.
+///
+/// Note: it's only read once when the app is built, changing it afterwards does nothing.
+///
+/// Example:
+/// ```no_run
+/// use glam::uvec2;
+/// use woody::app::App;
+/// use woody::window::PresentMode;
+/// use woody::window::WindowDescriptor;
+/// use woody::window::WindowMode;
+///
+/// App::builder()
+///     .window(WindowDescriptor {
+///         title: "Forest".to_string(),
+///         size: uvec2(1920, 1080),
+///         mode: WindowMode::BorderlessFullscreen,
+///         present_mode: PresentMode::Fifo,
+///         ..Default::default()
+///     })
+///     .run()
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowDescriptor {
+    pub title: String,
+    /// The logical size of the window when it's windowed.
+    pub size: UVec2,
+    /// The physical position of the window, or `None` to let the platform choose it.
+    pub position: Option<IVec2>,
+    pub mode: WindowMode,
+    pub decorations: bool,
+    pub resizable: bool,
+    pub present_mode: PresentMode,
+    /// The initial grab of the [`Cursor`].
+    pub cursor_grab: CursorGrab,
+}
+
+impl Default for WindowDescriptor {
+    fn default() -> Self {
+        Self {
+            title: "Woody Engine".to_string(),
+            size: UVec2::new(1280, 720),
+            position: None,
+            mode: WindowMode::Windowed,
+            decorations: true,
+            resizable: true,
+            present_mode: PresentMode::Mailbox,
+            cursor_grab: CursorGrab::None,
+        }
+    }
+}
+
+impl Resource for WindowDescriptor {}
+
+impl WindowDescriptor {
+    /// Creates the window described by this descriptor.
+    pub(crate) fn build(&self, event_loop: &EventLoop<()>) -> Result<Window, OsError> {
+        let mut builder = WindowBuilder::new()
+            .with_title(&self.title)
+            .with_inner_size(LogicalSize::new(self.size.x, self.size.y))
+            .with_decorations(self.decorations)
+            .with_resizable(self.resizable)
+            .with_fullscreen(self.fullscreen(event_loop));
+
+        if let Some(position) = self.position {
+            builder = builder.with_position(PhysicalPosition::new(position.x, position.y));
+        }
+
+        let window = builder.build(event_loop)?;
+        self.cursor_grab.apply(&window);
+
+        Ok(window)
+    }
+
+    fn fullscreen(&self, event_loop: &EventLoop<()>) -> Option<Fullscreen> {
+        match self.mode {
+            WindowMode::Windowed => None,
+            WindowMode::BorderlessFullscreen => Some(Fullscreen::Borderless(None)),
+            WindowMode::Fullscreen => {
+                // Picks the biggest video mode of the primary monitor, then the fastest one.
+                let video_mode = event_loop
+                    .primary_monitor()
+                    .or_else(|| event_loop.available_monitors().next())
+                    .and_then(|monitor| {
+                        monitor.video_modes().max_by_key(|mode| {
+                            let size = mode.size();
+                            (size.width * size.height, mode.refresh_rate_millihertz())
+                        })
+                    });
+
+                match video_mode {
+                    Some(video_mode) => Some(Fullscreen::Exclusive(video_mode)),
+                    None => {
+                        warn!("No video mode found for exclusive fullscreen, using borderless");
+                        Some(Fullscreen::Borderless(None))
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// How the window is displayed.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WindowMode {
+    #[default]
+    Windowed,
+    /// Covers the whole monitor without changing its video mode.
+    BorderlessFullscreen,
+    /// Takes exclusive control of the monitor with its biggest video mode.
+    Fullscreen,
+}
+
+/// How frames are presented to the window, which trades latency for tearing and power usage.
+///
+/// Note: when the device doesn't support the chosen mode, [`PresentMode::Fifo`] is used.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PresentMode {
+    /// Waits for the vertical blank, it's always supported.
+    Fifo,
+    /// Like [`PresentMode::Fifo`], but presents right away when a frame is late, which may tear.
+    FifoRelaxed,
+    /// Waits for the vertical blank replacing the queued frame, so it doesn't tear nor block.
+    #[default]
+    Mailbox,
+    /// Presents right away, which may tear.
+    Immediate,
+}
+
+/// How the cursor is kept inside the window.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CursorGrab {
+    #[default]
+    None,
+    /// The cursor can't leave the window.
+    Confined,
+    /// The cursor can't move, only the mouse motion is reported.
+    Locked,
+}
+
+impl CursorGrab {
+    /// Applies this grab to a window.
+    ///
+    /// Note: platforms only support some of the modes, so the other grabbing mode is tried when
+    /// the chosen one fails.
+    pub(crate) fn apply(self, window: &Window) {
+        let (mode, fallback) = match self {
+            CursorGrab::None => (CursorGrabMode::None, CursorGrabMode::None),
+            CursorGrab::Confined => (CursorGrabMode::Confined, CursorGrabMode::Locked),
+            CursorGrab::Locked => (CursorGrabMode::Locked, CursorGrabMode::Confined),
+        };
+
+        if let Err(err) = window
+            .set_cursor_grab(mode)
+            .or_else(|_| window.set_cursor_grab(fallback))
+        {
+            warn!("Could not grab the cursor with {self:?}: {err}");
+        }
+    }
+}
+
+/// A resource controlling the cursor over the window, applied at the end of the frame.
+///
+/// Example:
+/// ```no_run
+/// use woody::app::GameState;
+/// use woody::ecs::world::World;
+/// use woody::input::keyboard::KeyCode;
+/// use woody::input::keyboard::KeyboardEvent;
+/// use woody::input::MouseEvent;
+/// use woody::window::Cursor;
+///
+/// // Captures the mouse for an FPS camera on click, and frees it on Escape.
+/// fn capture(world: &mut World, _: GameState, _: MouseEvent) {
+///     world.resource_mut::<Cursor>().unwrap().lock();
+/// }
+///
+/// fn release(world: &mut World, _: GameState, event: KeyboardEvent) {
+///     if event.keycode == KeyCode::Escape {
+///         world.resource_mut::<Cursor>().unwrap().release();
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub grab: CursorGrab,
+    pub visible: bool,
+    /// The system icon shown over the window.
+    ///
+    /// Note: custom images aren't supported by the windowing backend yet.
+    pub icon: CursorIcon,
+    /// The physical position the cursor is moved to, see [`Cursor::set_position`].
+    warp: Option<(i32, i32)>,
+}
+
+impl Default for Cursor {
+    fn default() -> Self {
+        Self {
+            grab: CursorGrab::None,
+            visible: true,
+            icon: CursorIcon::Default,
+            warp: None,
+        }
+    }
+}
+
+impl Resource for Cursor {}
+
+impl Cursor {
+    /// Locks and hides the cursor, so only the mouse motion is reported, e.g. for FPS cameras.
+    pub fn lock(&mut self) {
+        self.grab = CursorGrab::Locked;
+        self.visible = false;
+    }
+
+    /// Frees and shows the cursor.
+    pub fn release(&mut self) {
+        self.grab = CursorGrab::None;
+        self.visible = true;
+    }
+
+    /// Moves the cursor to a physical position in the window.
+    pub fn set_position(&mut self, position: Vec2) {
+        self.warp = Some((position.x as i32, position.y as i32));
+    }
+
+    /// Applies the settings that changed since the `previous` ones to a window.
+    pub(crate) fn apply(&mut self, window: &Window, previous: &Cursor) {
+        if self.grab != previous.grab {
+            self.grab.apply(window);
+        }
+        if self.visible != previous.visible {
+            window.set_cursor_visible(self.visible);
+        }
+        if self.icon != previous.icon {
+            window.set_cursor_icon(self.icon);
+        }
+
+        if let Some((x, y)) = self.warp.take() {
+            if let Err(err) = window.set_cursor_position(PhysicalPosition::new(x, y)) {
+                warn!("Could not move the cursor: {err}");
+            }
+        }
+    }
+}